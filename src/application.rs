@@ -3,4 +3,5 @@ pub mod client;
 pub mod worker;
 pub mod handler;
 pub mod server;
-pub mod event;
\ No newline at end of file
+pub mod event;
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,11 @@
+use std::error::Error;
+use async_trait::async_trait;
+use crate::domain::audit::AuditEntry;
+
+#[async_trait]
+pub trait AuditLogger: Send + Sync {
+    /// Appends an entry. Entries are never changed or removed.
+    async fn record(&self, entry: &AuditEntry) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Every entry, oldest first.
+    async fn entries(&self) -> Result<Vec<AuditEntry>, Box<dyn Error + Send + Sync>>;
+}
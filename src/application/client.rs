@@ -1,12 +1,12 @@
 use std::error::Error;
 use async_trait::async_trait;
 use tokio::sync::mpsc::Receiver;
-use crate::domain::client::Message;
+use crate::domain::client::{Message, Reply};
 use crate::infrastructure::client::Client;
 
 #[async_trait]
 pub trait MessageGateway : Send + Sync {
-    async fn send_message(&self, client_name: &str, chat_id: &str, message: &str);
+    async fn send_message(&self, client_name: &str, chat_id: &str, message: Reply);
 }
 
 
@@ -2,8 +2,16 @@ mod auth;
 mod server;
 mod client;
 mod event;
+mod lockout;
+mod silence;
+mod maintenance;
+mod alert;
 
 pub use auth::*;
 pub use server::*;
 pub use client::*;
-pub use event::*;
\ No newline at end of file
+pub use event::*;
+pub use lockout::*;
+pub use silence::*;
+pub use maintenance::*;
+pub use alert::*;
\ No newline at end of file
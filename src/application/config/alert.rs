@@ -0,0 +1,17 @@
+use std::error::Error;
+use async_trait::async_trait;
+use crate::domain::alert::Alert;
+
+#[async_trait]
+pub trait AlertUseCase: Send + Sync {
+    /// Records a delivered alarm under a new short id.
+    async fn open_alert(&self, event_name: String, server_name: String, text: String) -> Result<Alert, Box<dyn Error + Send + Sync>>;
+    async fn find_alert(&self, id: &str) -> Result<Option<Alert>, Box<dyn Error + Send + Sync>>;
+    /// Stops the escalation of an alert. Fails if it is already acknowledged.
+    async fn acknowledge(&self, id: &str, acknowledged_by: String) -> Result<Alert, Box<dyn Error + Send + Sync>>;
+    async fn list_unacknowledged(&self) -> Result<Vec<Alert>, Box<dyn Error + Send + Sync>>;
+    /// Records that the first `escalated` levels of the alert's policy were sent.
+    async fn set_escalated(&self, id: &str, escalated: usize) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Drops alerts older than [crate::domain::alert::ALERT_RETENTION].
+    async fn prune(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
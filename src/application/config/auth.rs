@@ -1,11 +1,22 @@
 use std::error::Error;
 use async_trait::async_trait;
+use crate::domain::chat::{Chat, Role};
 
 #[async_trait]
 pub trait AuthUseCase : Send + Sync {
+    /// Clearing the password disables TOTP as well.
     async fn set_password(&self, password: Option<String>) -> Result<(), Box<dyn Error + Send + Sync>>;
     async fn validate_password(&mut self, password: String) -> bool;
-    async fn register(&mut self, client_name: String, identity: String) -> Result<(), Box<dyn Error + Send + Sync>>;
-    async fn authenticate(&mut self, client_name: String, identity: String) -> Option<String>;
+    async fn register(&mut self, client_name: String, identity: String) -> Result<String, Box<dyn Error + Send + Sync>>;
+    async fn authenticate(&mut self, client_name: String, identity: String) -> Option<Chat>;
+    async fn list_chats(&self) -> Result<Vec<Chat>, Box<dyn Error + Send + Sync>>;
+    /// Removes the chat, returning it so callers can clean up what belongs to it.
+    async fn revoke(&mut self, chat_id: &str) -> Result<Chat, Box<dyn Error + Send + Sync>>;
+    async fn set_role(&mut self, chat_id: &str, role: Role) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn set_servers(&mut self, chat_id: &str, servers: Option<Vec<String>>) -> Result<(), Box<dyn Error + Send + Sync>>;
     fn password_required(&self) -> bool;
+    /// Stores a TOTP secret, or disables the second factor with None.
+    async fn set_totp_secret(&self, secret: Option<String>) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn validate_totp(&mut self, code: &str) -> bool;
+    fn totp_required(&self) -> bool;
 }
\ No newline at end of file
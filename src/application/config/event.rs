@@ -14,4 +14,5 @@ pub trait EventSubscribeUseCase: Send + Sync {
     async fn subscribe(&self, chat_id: String, event_name: String) -> Result<(), Box<dyn Error + Send + Sync>>;
     async fn list_subscribed_event(&self, chat_id: String) -> Result<Vec<EventConfig>, Box<dyn Error + Send + Sync>>;
     async fn unsubscribe(&self, chat_id: String, event_name: String) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn unsubscribe_all(&self, chat_id: String) -> Result<(), Box<dyn Error + Send + Sync>>;
 }
\ No newline at end of file
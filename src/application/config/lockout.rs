@@ -0,0 +1,15 @@
+use std::error::Error;
+use async_trait::async_trait;
+use crate::domain::lockout::Lockout;
+
+#[async_trait]
+pub trait LockoutUseCase: Send + Sync {
+    /// Seconds left until the chat may try `/register` again, if the chat or registration is locked.
+    async fn locked_for(&self, client_name: &str, identity: &str) -> Result<Option<u64>, Box<dyn Error + Send + Sync>>;
+    /// Records a failed attempt, returning the lockout if it is the first since failures were forgotten.
+    /// Further failures extend the lockout without returning it again.
+    async fn record_failure(&self, client_name: &str, identity: &str) -> Result<Option<Lockout>, Box<dyn Error + Send + Sync>>;
+    async fn record_success(&self, client_name: &str, identity: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Forgets all failures and lifts every lockout.
+    async fn reset(&self) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
@@ -0,0 +1,13 @@
+use std::error::Error;
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use crate::domain::config::MaintenanceConfig;
+
+#[async_trait]
+pub trait MaintenanceUseCase: Send + Sync {
+    async fn add_maintenance(&self, maintenance_config: MaintenanceConfig) -> Result<(), Box<dyn Error + Send + Sync>>;
+    async fn list_maintenance(&self) -> Result<Vec<MaintenanceConfig>, Box<dyn Error + Send + Sync>>;
+    async fn remove_maintenance(&self, name: &str) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// End of the current maintenance of the server, if it is in one.
+    async fn maintenance_until(&self, server_name: &str) -> Result<Option<DateTime<Local>>, Box<dyn Error + Send + Sync>>;
+}
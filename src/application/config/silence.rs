@@ -0,0 +1,16 @@
+use std::error::Error;
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::domain::silence::{Silence, SilenceKind};
+
+#[async_trait]
+pub trait SilenceUseCase: Send + Sync {
+    /// Mutes alarms of an event or server for `duration`, on behalf of the given chat.
+    async fn silence(&self, target: String, kind: SilenceKind, duration: Duration, reason: Option<String>, client_name: String, identity: String) -> Result<Silence, Box<dyn Error + Send + Sync>>;
+    /// Silences which have not expired yet.
+    async fn list_silences(&self) -> Result<Vec<Silence>, Box<dyn Error + Send + Sync>>;
+    async fn unsilence(&self, id: &str) -> Result<Silence, Box<dyn Error + Send + Sync>>;
+    async fn find_silence(&self, event_name: &str, server_name: &str) -> Result<Option<Silence>, Box<dyn Error + Send + Sync>>;
+    /// Removes the expired silences and returns them, so their chats can be told.
+    async fn take_expired(&self) -> Result<Vec<Silence>, Box<dyn Error + Send + Sync>>;
+}
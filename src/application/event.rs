@@ -1,3 +1,4 @@
 pub mod receiver;
 pub mod dto;
 pub mod checker;
+pub mod escalation;
\ No newline at end of file
@@ -1,13 +1,14 @@
 use std::pin::Pin;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use derive_new::new;
+use log::error;
 use tokio::sync::mpsc::Sender;
 use tokio_stream::StreamExt;
 use crate::application::event::dto::EventMessage;
 use crate::application::server::ServerManager;
 use crate::domain::config::Config;
-use crate::domain::event::{Event, EventKind};
+use crate::domain::event::{format_duration, Captures, Event, EventKind, HealthTracker};
 use crate::domain::file_accessor::FileAccessor;
 
 #[derive(new)]
@@ -16,6 +17,7 @@ pub struct GeneralEventChecker {
     server_manager: Arc<dyn ServerManager>,
     tx: Sender<EventMessage>,
     health_event_checker: Box<dyn EventChecker>,
+    health_transition_event_checker: Box<dyn EventChecker>,
     log_event_checker: Box<dyn EventChecker>
 }
 
@@ -25,7 +27,16 @@ impl GeneralEventChecker {
         let config = self.config_file_accessor.read()
             .await.unwrap();
         let events: Vec<Event> = config.events.into_iter()
-            .map(|event_config|{Event::from(event_config)})
+            .filter_map(|event_config| {
+                let name = event_config.name.clone();
+                match Event::try_from(event_config) {
+                    Ok(event) => Some(event),
+                    Err(e) => {
+                        error!("[GeneralEventChecker] Err: Invalid event '{name}': {e}");
+                        None
+                    }
+                }
+            })
             .collect();
         for event in events {
             self.check(event);
@@ -38,6 +49,10 @@ impl GeneralEventChecker {
                 self.health_event_checker
                     .check(event, self.server_manager.clone(), self.tx.clone())
             },
+            EventKind::HealthTransition {server_name: _} => {
+                self.health_transition_event_checker
+                    .check(event, self.server_manager.clone(), self.tx.clone())
+            },
             EventKind::Log {server_name: _, keyword: _} => {
                 self.log_event_checker
                     .check(event, self.server_manager.clone(), self.tx.clone())
@@ -56,18 +71,69 @@ pub struct HealthEventChecker;
 
 impl EventChecker for HealthEventChecker {
     fn check(&self, event: Event, server_manager: Arc<dyn ServerManager>, tx: Sender<EventMessage>) {
-        if let EventKind::Health { server_name, keyword } = event.event_kind {
-            let event_name = event.name;
+        if let EventKind::Health { server_name, keyword } = event.event_kind.clone() {
             tokio::spawn(async move {
+                let mut throttle = event.throttle();
+                loop {
+                    let health = server_manager.healthcheck(server_name.as_str()).await.to_string();
+                    let texts = match event.matcher.find(health.as_str()) {
+                        Some(captures) => {
+                            let text = event.alarm_text(server_name.as_str(), health.as_str(), captures, || {
+                                let found = if event.matcher.is_negated() { "not found" } else { "found" };
+                                format!("Keyword '{}' {} in health check of server '{}'", keyword, found, server_name)
+                            });
+                            throttle.alarm(text, Instant::now())
+                        },
+                        None => throttle.flush(Instant::now())
+                    };
+                    send_all(&tx, event.name.as_str(), server_name.as_str(), texts).await;
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                }
+            });
+        }
+    }
+}
+
+#[derive(new)]
+pub struct HealthTransitionEventChecker {
+    #[new(value = "Duration::from_secs(30)")]
+    interval: Duration
+}
+
+impl HealthTransitionEventChecker {
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+impl EventChecker for HealthTransitionEventChecker {
+    fn check(&self, event: Event, server_manager: Arc<dyn ServerManager>, tx: Sender<EventMessage>) {
+        if let EventKind::HealthTransition { server_name } = event.event_kind.clone() {
+            let interval = self.interval;
+            tokio::spawn(async move {
+                let mut tracker = HealthTracker::default();
                 loop {
                     let health = server_manager.healthcheck(server_name.as_str()).await;
-                    if health.to_string().contains(keyword.as_str()) {
-                        let _ = tx.send(EventMessage {
-                            event_name: event_name.clone(),
-                            text: format!("Keyword '{}' found in health check of server '{}'", keyword, server_name),
-                        }).await;
+                    if let Some(transition) = tracker.observe(health, Instant::now()) {
+                        // Templates can use {{from}}, {{to}} and {{outage}}
+                        let captures = Captures::from([
+                            (String::from("from"), transition.from.as_ref().map(ToString::to_string).unwrap_or_default()),
+                            (String::from("to"), transition.to.to_string()),
+                            (String::from("outage"), transition.outage.map(format_duration).unwrap_or_default())
+                        ]);
+                        let text = event.alarm_text(server_name.as_str(), transition.to.to_string().as_str(), captures, || {
+                            transition.text(server_name.as_str())
+                        });
+                        if tx.send(EventMessage {
+                            event_name: event.name.clone(),
+                            server_name: server_name.clone(),
+                            text,
+                        }).await.is_err() {
+                            break;
+                        }
                     }
-                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    tokio::time::sleep(interval).await;
                 }
             });
         }
@@ -80,23 +146,187 @@ pub struct LogEventChecker;
 impl EventChecker for LogEventChecker {
     fn check(&self, event: Event, server_manager: Arc<dyn ServerManager>, tx: Sender<EventMessage>) {
 
-        if let EventKind::Log {server_name, keyword} = event.event_kind {
-            let event_name = event.name;
+        if let EventKind::Log {server_name, keyword} = event.event_kind.clone() {
             tokio::spawn(async move {
                 let stream = server_manager.logs_stream(server_name.as_str()).await;
 
                 if let Some(stream) = stream {
                     let mut stream = Pin::from(stream);
-                    while let Some(line) = stream.next().await {
-                        if line.contains(keyword.as_str()) {
-                            let _ = tx.send(EventMessage {
-                                event_name: event_name.clone(),
-                                text: format!("Keyword '{}' found in logs of server '{}'\nLog: {}", keyword, server_name, line),
-                            }).await;
+                    let mut throttle = event.throttle();
+                    loop {
+                        // Wakes up when a cooldown ends, so collapsed alarms are reported without waiting for another line
+                        let deadline = throttle.next_deadline();
+                        let line = tokio::select! {
+                            line = stream.next() => match line {
+                                Some(line) => line,
+                                None => break
+                            },
+                            _ = async {
+                                match deadline {
+                                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                                    None => std::future::pending().await
+                                }
+                            } => {
+                                send_all(&tx, event.name.as_str(), server_name.as_str(), throttle.flush(Instant::now())).await;
+                                continue;
+                            }
+                        };
+
+                        if let Some(captures) = event.matcher.find(line.as_str()) {
+                            let text = event.alarm_text(server_name.as_str(), line.as_str(), captures, || {
+                                format!("Keyword '{}' found in logs of server '{}'\nLog: {}", keyword, server_name, line)
+                            });
+                            send_all(&tx, event.name.as_str(), server_name.as_str(), throttle.alarm(text, Instant::now())).await;
                         }
                     }
+                    // Report what was collapsed before the stream ended
+                    send_all(&tx, event.name.as_str(), server_name.as_str(), throttle.drain()).await;
                 }
             });
         }
     }
 }
+
+async fn send_all(tx: &Sender<EventMessage>, event_name: &str, server_name: &str, texts: Vec<String>) {
+    for text in texts {
+        let _ = tx.send(EventMessage {
+            event_name: event_name.to_string(),
+            server_name: server_name.to_string(),
+            text,
+        }).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use tokio::sync::mpsc;
+    use tokio_stream::Stream;
+    use super::*;
+    use crate::domain::config::{EventConfig, MatchMode};
+    use crate::domain::server::health::Health;
+
+    struct StubServerManager;
+
+    /// Reports the scripted health values in order, then stays at the last one.
+    struct ScriptedServerManager {
+        health: std::sync::Mutex<Vec<Health>>
+    }
+
+    #[async_trait]
+    impl ServerManager for ScriptedServerManager {
+        async fn kill(&self, _name: &str) -> bool { false }
+        async fn restart(&self, _name: &str) -> bool { false }
+        async fn healthcheck(&self, _name: &str) -> Health {
+            let mut health = self.health.lock().unwrap();
+            if health.len() > 1 { health.remove(0) } else { health[0].clone() }
+        }
+        async fn healthcheck_all(&self) -> Vec<(&str, Health)> { vec![] }
+        async fn logs(&self, _name: &str, _n: i32) -> Option<String> { None }
+        async fn logs_stream(&self, _name: &str) -> Option<Box<dyn Stream<Item=String> + Send>> { None }
+    }
+
+    #[async_trait]
+    impl ServerManager for StubServerManager {
+        async fn kill(&self, _name: &str) -> bool { false }
+        async fn restart(&self, _name: &str) -> bool { false }
+        async fn healthcheck(&self, _name: &str) -> Health { Health::Down }
+        async fn healthcheck_all(&self) -> Vec<(&str, Health)> { vec![] }
+        async fn logs(&self, _name: &str, _n: i32) -> Option<String> { None }
+        async fn logs_stream(&self, _name: &str) -> Option<Box<dyn Stream<Item=String> + Send>> {
+            let lines = ["GET / status=200", "GET /a status=503 ERROR E_DB", "GET /b status=404 ERROR E_NF"];
+            Some(Box::new(tokio_stream::iter(lines.map(String::from))))
+        }
+    }
+
+    fn event(r#type: &str, keyword: &str, match_mode: MatchMode, negate: bool, message: Option<&str>) -> Event {
+        Event::try_from(EventConfig {
+            r#type: r#type.to_string(),
+            name: String::from("main-error"),
+            target: String::from("main"),
+            keyword: keyword.to_string(),
+            match_mode,
+            negate,
+            message: message.map(String::from),
+            cooldown: None,
+            dedup_window: None,
+            escalation: Vec::new()
+        }).unwrap()
+    }
+
+    #[tokio::test]
+    async fn log_event_interpolates_captures() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let event = event("logs", r"status=(5\d\d) ERROR (?P<code>\w+)", MatchMode::Regex, false, Some("{{server}} returned {{1}} ({{code}})"));
+
+        LogEventChecker::new().check(event, Arc::new(StubServerManager), tx);
+
+        let message = rx.recv().await.unwrap();
+        assert_eq!(message.event_name, "main-error");
+        assert_eq!(message.text, "main returned 503 (E_DB)");
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn health_event_negated() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let event = event("health", "healthy", MatchMode::CaseInsensitive, true, None);
+
+        HealthEventChecker::new().check(event, Arc::new(StubServerManager), tx);
+
+        let message = rx.recv().await.unwrap();
+        assert_eq!(message.text, "Keyword 'healthy' not found in health check of server 'main'");
+    }
+
+    #[test]
+    fn negate_is_only_for_health_events() {
+        let config = |r#type: &str| EventConfig {
+            r#type: r#type.to_string(),
+            name: String::from("main-error"),
+            target: String::from("main"),
+            keyword: String::from("ERROR"),
+            match_mode: MatchMode::Substring,
+            negate: true,
+            message: None,
+            cooldown: None,
+            dedup_window: None,
+            escalation: Vec::new()
+        };
+
+        assert!(Event::try_from(config("health")).is_ok());
+        assert!(Event::try_from(config("logs")).is_err());
+        assert!(Event::try_from(config("health-transition")).is_err());
+    }
+
+    #[tokio::test]
+    async fn health_transition_reports_changes() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let server_manager = ScriptedServerManager {
+            health: std::sync::Mutex::new(vec![Health::Healthy, Health::Healthy, Health::Down, Health::Down, Health::Healthy])
+        };
+        let event = event("health-transition", "", MatchMode::Substring, false, None);
+
+        HealthTransitionEventChecker::new()
+            .with_interval(Duration::from_millis(1))
+            .check(event, Arc::new(server_manager), tx);
+
+        assert_eq!(rx.recv().await.unwrap().text, "Server 'main' is Down (was Healthy)");
+        let recovered = rx.recv().await.unwrap().text;
+        assert!(recovered.starts_with("Server 'main' recovered after "), "{recovered}");
+        assert!(recovered.ends_with(" (was Down)"), "{recovered}");
+        assert!(tokio::time::timeout(Duration::from_millis(50), rx.recv()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn log_event_collapses_within_cooldown() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let mut event = event("logs", "ERROR", MatchMode::Substring, false, Some("{{server}} logged an error"));
+        event.cooldown = Some(Duration::from_secs(60));
+
+        LogEventChecker::new().check(event, Arc::new(StubServerManager), tx);
+
+        assert_eq!(rx.recv().await.unwrap().text, "main logged an error");
+        assert_eq!(rx.recv().await.unwrap().text, "main logged an error\n(occurred 1 more time in the last 1m 0s)");
+        assert!(rx.recv().await.is_none());
+    }
+}
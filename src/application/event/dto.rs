@@ -1,4 +1,6 @@
+#[derive(Clone, Debug)]
 pub struct EventMessage {
     pub event_name: String,
+    pub server_name: String,
     pub text: String
 }
@@ -0,0 +1,240 @@
+use std::error::Error;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use log::error;
+use crate::application::client::MessageGateway;
+use crate::application::config::{AlertUseCase, EventConfigUseCase, MaintenanceUseCase, SilenceUseCase};
+use crate::application::worker::Worker;
+use crate::domain::alert::Alert;
+use crate::domain::chat::ChatList;
+use crate::domain::client::{Button, Reply, Severity};
+use crate::domain::event::format_duration;
+use crate::domain::file_accessor::FileAccessor;
+
+/// Sends alerts nobody acknowledged to the next level of their event's escalation policy.
+#[derive(new)]
+pub struct EscalationWorker {
+    message_gateway: Arc<dyn MessageGateway>,
+    alert_use_case: Arc<dyn AlertUseCase>,
+    event_config_use_case: Arc<dyn EventConfigUseCase>,
+    silence_use_case: Arc<dyn SilenceUseCase>,
+    maintenance_use_case: Arc<dyn MaintenanceUseCase>,
+    chat_list_file_accessor: Arc<dyn FileAccessor<ChatList>>
+}
+
+#[async_trait]
+impl Worker for EscalationWorker {
+    async fn on_tick(&mut self) -> bool {
+        if let Err(e) = self.escalate().await {
+            error!("[EscalationWorker] Err: {e}");
+        }
+        true
+    }
+
+    fn get_name(&self) -> &str {
+        "escalation"
+    }
+
+    fn interval(&self) -> i32 {
+        30
+    }
+}
+
+impl EscalationWorker {
+    pub async fn escalate(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.alert_use_case.prune().await?;
+        let alerts = self.alert_use_case.list_unacknowledged().await?;
+        if alerts.is_empty() {
+            return Ok(());
+        }
+        let events = self.event_config_use_case.list_event().await?;
+        let chats = self.chat_list_file_accessor.read().await?.chats;
+
+        let now = Utc::now();
+        for alert in alerts {
+            let policy = match events.iter().find(|event| event.name == alert.event_name) {
+                Some(event) => &event.escalation,
+                None => continue
+            };
+            let due = alert.due_levels(policy, now);
+            if due.is_empty() {
+                continue;
+            }
+            // Silencing or pausing the server afterwards means someone is on it, like an /ack
+            if let Some(by) = self.suppressed_by(&alert).await {
+                if let Err(e) = self.alert_use_case.acknowledge(alert.id.as_str(), by).await {
+                    error!("[EscalationWorker] Err: {e}");
+                }
+                continue;
+            }
+
+            let unacknowledged = (now - alert.created_at).to_std().unwrap_or_default();
+            for level in &policy[due.clone()] {
+                for chat in chats.iter().filter(|chat| level.chat_ids.contains(&chat.id)) {
+                    let reply = Reply::titled(format!("Escalated: {}", alert.event_name))
+                        .with_severity(Severity::Warning)
+                        .with_text(format!("{}\nNot acknowledged for {}.", alert.text, format_duration(unacknowledged)))
+                        .with_field("Alert", alert.id.as_str())
+                        .with_buttons(vec![vec![Button::new(String::from("Acknowledge"), alert.ack_command())]]);
+                    self.message_gateway.send_message(chat.client_name.as_str(), chat.identity.as_str(), reply).await;
+                }
+            }
+            self.alert_use_case.set_escalated(alert.id.as_str(), due.end).await?;
+        }
+        Ok(())
+    }
+
+    /// The silence or maintenance muting the alert's alarms now, if any.
+    /// Fails open like the event manager, so unreadable files do not stop escalations.
+    async fn suppressed_by(&self, alert: &Alert) -> Option<String> {
+        match self.silence_use_case.find_silence(alert.event_name.as_str(), alert.server_name.as_str()).await {
+            Ok(Some(silence)) => return Some(format!("silence {}", silence.id)),
+            Ok(None) => {},
+            Err(e) => error!("[EscalationWorker] Err: Fail to read silences, escalating anyway: {e}")
+        }
+        match self.maintenance_use_case.maintenance_until(alert.server_name.as_str()).await {
+            Ok(until) => until.map(|until| format!("maintenance until {}", until.format("%Y-%m-%d %H:%M"))),
+            Err(e) => {
+                error!("[EscalationWorker] Err: Fail to read maintenance windows, escalating anyway: {e}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use chrono::{Local, TimeDelta};
+    use super::*;
+    use crate::domain::alert::AlertList;
+    use crate::domain::chat::Chat;
+    use crate::domain::config::{Config, EscalationLevel, EventConfig, EventSubscribeList, MaintenanceConfig, MatchMode, Schedule};
+    use crate::domain::silence::{Silence, SilenceKind, SilenceList};
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+    use crate::infrastructure::config::{AlertAdapter, EventConfigAdapter, MaintenanceAdapter, SilenceAdapter};
+
+    #[derive(Default)]
+    struct RecordingGateway {
+        sent: Mutex<Vec<(String, String)>>
+    }
+
+    #[async_trait]
+    impl MessageGateway for RecordingGateway {
+        async fn send_message(&self, _client_name: &str, chat_id: &str, message: Reply) {
+            self.sent.lock().unwrap().push((chat_id.to_string(), message.to_plain_text()));
+        }
+    }
+
+    fn alert(id: &str, age: TimeDelta, acknowledged_by: Option<&str>) -> Alert {
+        Alert {
+            id: id.to_string(),
+            event_name: String::from("main-down"),
+            server_name: String::from("main"),
+            text: String::from("main is down"),
+            created_at: Utc::now() - age,
+            escalated: 0,
+            acknowledged_by: acknowledged_by.map(str::to_string)
+        }
+    }
+
+    fn setup(config: Config, alert_list: AlertList, silence_list: SilenceList) -> (EscalationWorker, Arc<RecordingGateway>, Arc<AlertAdapter>) {
+        let mut chat_list = ChatList::new();
+        for identity in ["1", "2", "3"] {
+            let mut chat = Chat::new(String::from("telegram"), identity.to_string());
+            chat.id = format!("chat-{identity}");
+            chat_list.chats.push(chat);
+        }
+        let config_file_accessor = Arc::new(MemoryFileAccessor::new(config));
+        let gateway = Arc::new(RecordingGateway::default());
+        let alert_adapter = Arc::new(AlertAdapter::new(Arc::new(MemoryFileAccessor::new(alert_list))));
+        let worker = EscalationWorker::new(
+            gateway.clone(),
+            alert_adapter.clone(),
+            Arc::new(EventConfigAdapter::new(
+                config_file_accessor.clone(),
+                Arc::new(MemoryFileAccessor::new(EventSubscribeList::new()))
+            )),
+            Arc::new(SilenceAdapter::new(Arc::new(MemoryFileAccessor::new(silence_list)))),
+            Arc::new(MaintenanceAdapter::new(config_file_accessor)),
+            Arc::new(MemoryFileAccessor::new(chat_list))
+        );
+        (worker, gateway, alert_adapter)
+    }
+
+    fn config() -> Config {
+        let mut config = Config::new(None);
+        config.events.push(EventConfig {
+            r#type: String::from("health"),
+            name: String::from("main-down"),
+            target: String::from("main"),
+            keyword: String::from("DOWN"),
+            match_mode: MatchMode::Substring,
+            negate: false,
+            message: None,
+            cooldown: None,
+            dedup_window: None,
+            escalation: vec![
+                EscalationLevel { delay: 600, chat_ids: vec![String::from("chat-2")] },
+                EscalationLevel { delay: 1800, chat_ids: vec![String::from("chat-3")] }
+            ]
+        });
+        config
+    }
+
+    #[tokio::test]
+    async fn escalates_unacknowledged_alerts_once_per_level() {
+        let mut alert_list = AlertList::new();
+        alert_list.alerts.push(alert("a1", TimeDelta::minutes(11), None));
+        alert_list.alerts.push(alert("a2", TimeDelta::minutes(40), Some("telegram:1")));
+        let (worker, gateway, alert_adapter) = setup(config(), alert_list, SilenceList::new());
+
+        worker.escalate().await.unwrap();
+        worker.escalate().await.unwrap();
+        assert_eq!(gateway.sent.lock().unwrap().clone(), vec![
+            (String::from("2"), String::from("[WARNING] Escalated: main-down\nmain is down\nNot acknowledged for 11m 0s.\nAlert: a1"))
+        ]);
+        assert_eq!(alert_adapter.find_alert("a1").await.unwrap().unwrap().escalated, 1);
+
+        alert_adapter.acknowledge("a1", String::from("telegram:2")).await.unwrap();
+        assert!(alert_adapter.acknowledge("a1", String::from("telegram:3")).await.is_err());
+        assert!(alert_adapter.list_unacknowledged().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn silence_or_maintenance_stops_escalation() {
+        let mut alert_list = AlertList::new();
+        alert_list.alerts.push(alert("a1", TimeDelta::minutes(11), None));
+        let mut silence_list = SilenceList::new();
+        silence_list.silences.push(Silence {
+            id: String::from("s1"),
+            target: String::from("main"),
+            kind: SilenceKind::Server,
+            until: Utc::now() + TimeDelta::minutes(30),
+            reason: None,
+            client_name: String::from("telegram"),
+            identity: String::from("1")
+        });
+        let (worker, gateway, alert_adapter) = setup(config(), alert_list.clone(), silence_list);
+
+        worker.escalate().await.unwrap();
+        assert!(gateway.sent.lock().unwrap().is_empty());
+        assert_eq!(alert_adapter.find_alert("a1").await.unwrap().unwrap().acknowledged_by.as_deref(), Some("silence s1"));
+
+        let mut config = config();
+        config.maintenance_windows.push(MaintenanceConfig {
+            name: String::from("backup"),
+            server_name: String::from("main"),
+            schedule: Schedule { days: Vec::new(), time: (Local::now() - TimeDelta::minutes(1)).time() },
+            duration: 60 * 60
+        });
+        let (worker, gateway, alert_adapter) = setup(config, alert_list, SilenceList::new());
+
+        worker.escalate().await.unwrap();
+        assert!(gateway.sent.lock().unwrap().is_empty());
+        let acknowledged_by = alert_adapter.find_alert("a1").await.unwrap().unwrap().acknowledged_by.unwrap();
+        assert!(acknowledged_by.starts_with("maintenance until "), "{acknowledged_by}");
+    }
+}
@@ -1,31 +1,82 @@
 use std::error::Error;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use async_trait::async_trait;
 use derive_new::new;
+use log::{debug, error};
 use tokio::sync::mpsc::Receiver;
+use tokio::time::Interval;
 use crate::application::client::MessageGateway;
+use crate::application::config::{AlertUseCase, MaintenanceUseCase, SilenceUseCase};
 use crate::application::event::dto::EventMessage;
 use crate::application::worker::Worker;
 use crate::domain::chat::{Chat, ChatList};
-use crate::domain::config::EventSubscribeList;
+use crate::domain::client::{Button, Reply, Severity};
+use crate::domain::config::{DigestConfig, EventSubscribeList};
+use crate::domain::event::DigestBuffer;
 use crate::domain::file_accessor::{FileAccessor};
 
+/// Chats are keyed by client name and identity, which is where the digest is sent.
+type ChatKey = (String, String);
+
+/// An alarm as sent to chats, with the id of its alert unless it could not be opened.
+#[derive(Clone, Debug)]
+struct Delivery {
+    event_name: String,
+    text: String,
+    alert_id: Option<String>
+}
+
+const SILENCE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(new)]
 pub struct EventManager {
     rx: Receiver<EventMessage>,
     message_gateway: Arc<dyn MessageGateway>,
     chat_list_file_accessor: Arc<dyn FileAccessor<ChatList>>,
     subscribe_file_accessor: Arc<dyn FileAccessor<EventSubscribeList>>,
+    silence_use_case: Arc<dyn SilenceUseCase>,
+    maintenance_use_case: Arc<dyn MaintenanceUseCase>,
+    alert_use_case: Arc<dyn AlertUseCase>,
+    /// When to look for expired silences
+    #[new(value = "tokio::time::interval_at(tokio::time::Instant::now() + SILENCE_CHECK_INTERVAL, SILENCE_CHECK_INTERVAL)")]
+    silence_check: Interval,
+    /// None sends every alarm right away
+    #[new(default)]
+    digest: Option<DigestBuffer<ChatKey, Delivery>>,
 }
 
 #[async_trait]
 impl Worker for EventManager {
     async fn on_tick(&mut self) -> bool {
-        if let Some(message) = self.rx.recv().await {
-            let _ = self.handle(message).await;
-            true
-        } else {
-            false
+        // Also wakes up when a digest is due, as no further alarm may come
+        let deadline = self.digest.as_ref().and_then(|digest| digest.next_deadline());
+        tokio::select! {
+            message = self.rx.recv() => match message {
+                Some(message) => {
+                    if let Err(e) = self.handle(message).await {
+                        error!("[EventManager] Err: {e}");
+                    }
+                    true
+                },
+                None => {
+                    self.flush_digest(true).await;
+                    false
+                }
+            },
+            _ = async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await
+                }
+            } => {
+                self.flush_digest(false).await;
+                true
+            },
+            _ = self.silence_check.tick() => {
+                self.expire_silences().await;
+                true
+            }
         }
     }
 
@@ -39,8 +90,31 @@ impl Worker for EventManager {
 }
 
 impl EventManager {
-    pub async fn handle(&self, event_message: EventMessage)
+    pub fn with_digest(mut self, digest_config: &DigestConfig) -> Self {
+        self.digest = Some(DigestBuffer::new(Duration::from_secs(digest_config.interval), digest_config.max_items));
+        self
+    }
+
+    pub async fn handle(&mut self, event_message: EventMessage)
                         -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Fails open: an unreadable silence.json or config must not mute every alarm
+        match self.silence_use_case
+            .find_silence(event_message.event_name.as_str(), event_message.server_name.as_str()).await {
+            Ok(Some(silence)) => {
+                debug!("alarm of '{}' silenced by {}", event_message.event_name, silence.id);
+                return Ok(());
+            },
+            Ok(None) => {},
+            Err(e) => error!("[EventManager] Err: Fail to read silences, sending the alarm: {e}")
+        }
+        match self.maintenance_use_case.maintenance_until(event_message.server_name.as_str()).await {
+            Ok(Some(until)) => {
+                debug!("alarm of '{}' suppressed by maintenance until {}", event_message.event_name, until);
+                return Ok(());
+            },
+            Ok(None) => {},
+            Err(e) => error!("[EventManager] Err: Fail to read maintenance windows, sending the alarm: {e}")
+        }
         let subscribe_list = self.subscribe_file_accessor.read().await?;
         let chat_ids = match subscribe_list
             .find_subscribe(event_message.event_name.as_str()) {
@@ -52,13 +126,309 @@ impl EventManager {
             .into_iter()
             .filter(|chat| {chat_ids.contains(&chat.id)})
             .collect();
+        if chats.is_empty() {
+            return Ok(());
+        }
+
+        // One alert for all chats, so a single /ack stops the escalation.
+        // Without it the alarm is still sent, it just cannot be acknowledged.
+        let alert_id = match self.alert_use_case.open_alert(
+            event_message.event_name.clone(), event_message.server_name.clone(), event_message.text.clone()
+        ).await {
+            Ok(alert) => Some(alert.id),
+            Err(e) => {
+                error!("[EventManager] Err: Fail to open alert, sending the alarm without an id: {e}");
+                None
+            }
+        };
+        let delivery = Delivery {
+            event_name: event_message.event_name,
+            text: event_message.text,
+            alert_id
+        };
         for chat in chats {
-            self.message_gateway.send_message(
-                chat.client_name.as_str(),
-                chat.identity.as_str(),
-                event_message.text.as_str()
-            ).await;
+            let key = (chat.client_name, chat.identity);
+            let items = match self.digest.as_mut() {
+                Some(digest) => match digest.push(key.clone(), delivery.clone(), Instant::now()) {
+                    Some(items) => items,
+                    None => continue
+                },
+                None => vec![delivery.clone()]
+            };
+            self.send(key, items).await;
         }
         Ok(())
     }
+
+    async fn flush_digest(&mut self, all: bool) {
+        let due = match self.digest.as_mut() {
+            Some(digest) if all => digest.drain(),
+            Some(digest) => digest.flush(Instant::now()),
+            None => return
+        };
+        for (key, items) in due {
+            self.send(key, items).await;
+        }
+    }
+
+    /// Tells each chat which created an expired silence that its alarms are back.
+    pub async fn expire_silences(&self) {
+        let expired = match self.silence_use_case.take_expired().await {
+            Ok(expired) => expired,
+            Err(e) => {
+                error!("[EventManager] Err: {e}");
+                return;
+            }
+        };
+        for silence in expired {
+            let reply = Reply::titled("Silence expired")
+                .with_severity(Severity::Info)
+                .with_text(format!("Alarms of '{}' are sent again. (id: {})", silence.target, silence.id));
+            self.message_gateway.send_message(silence.client_name.as_str(), silence.identity.as_str(), reply).await;
+        }
+    }
+
+    async fn send(&self, (client_name, identity): ChatKey, items: Vec<Delivery>) {
+        let reply = match &items[..] {
+            [item] => match &item.alert_id {
+                Some(id) => Reply::from(item.text.as_str())
+                    .with_field("Alert", id.as_str())
+                    .with_buttons(vec![vec![Button::new(String::from("Acknowledge"), format!("/ack {id}"))]]),
+                None => Reply::from(item.text.as_str())
+            },
+            _ => items.iter().fold(
+                Reply::titled(format!("Digest: {} alarms", items.len())),
+                |reply, item| match &item.alert_id {
+                    Some(id) => reply.with_field(format!("{} [{id}]", item.event_name), item.text.as_str()),
+                    None => reply.with_field(item.event_name.as_str(), item.text.as_str())
+                }
+            )
+                .with_buttons(items.iter()
+                    .filter_map(|item| item.alert_id.as_ref().map(|id| {
+                        vec![Button::new(format!("Acknowledge {} [{id}]", item.event_name), format!("/ack {id}"))]
+                    }))
+                    .collect())
+        };
+        self.message_gateway.send_message(client_name.as_str(), identity.as_str(), reply).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::mpsc;
+    use super::*;
+    use chrono::{Local, TimeDelta, Utc};
+    use crate::domain::config::{Config, EventSubscribe, MaintenanceConfig, Schedule};
+    use crate::domain::silence::{Silence, SilenceKind, SilenceList};
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+    use crate::domain::alert::{Alert, AlertList};
+    use crate::infrastructure::config::{AlertAdapter, MaintenanceAdapter, SilenceAdapter};
+
+    #[derive(Default)]
+    struct RecordingGateway {
+        sent: Mutex<Vec<(String, String)>>
+    }
+
+    #[async_trait]
+    impl MessageGateway for RecordingGateway {
+        async fn send_message(&self, _client_name: &str, chat_id: &str, message: Reply) {
+            self.sent.lock().unwrap().push((chat_id.to_string(), message.to_plain_text()));
+        }
+    }
+
+    fn setup() -> (EventManager, mpsc::Sender<EventMessage>, Arc<RecordingGateway>) {
+        setup_with(SilenceList::new(), Config::new(None))
+    }
+
+    fn setup_with(silence_list: SilenceList, config: Config) -> (EventManager, mpsc::Sender<EventMessage>, Arc<RecordingGateway>) {
+        let mut chat_list = ChatList::new();
+        for identity in ["1", "2"] {
+            let mut chat = Chat::new(String::from("telegram"), identity.to_string());
+            chat.id = format!("chat-{identity}");
+            chat_list.chats.push(chat);
+        }
+        let mut subscribe_list = EventSubscribeList::new();
+        subscribe_list.subscribes.push(EventSubscribe {
+            event_name: String::from("main-down"),
+            chat_ids: vec![String::from("chat-1"), String::from("chat-2")]
+        });
+        subscribe_list.subscribes.push(EventSubscribe {
+            event_name: String::from("db-down"),
+            chat_ids: vec![String::from("chat-1")]
+        });
+
+        let (tx, rx) = mpsc::channel(16);
+        let gateway = Arc::new(RecordingGateway::default());
+        let event_manager = EventManager::new(
+            rx,
+            gateway.clone(),
+            Arc::new(MemoryFileAccessor::new(chat_list)),
+            Arc::new(MemoryFileAccessor::new(subscribe_list)),
+            Arc::new(SilenceAdapter::new(Arc::new(MemoryFileAccessor::new(silence_list)))),
+            Arc::new(MaintenanceAdapter::new(Arc::new(MemoryFileAccessor::new(config)))),
+            // Numbered in order of the alarms
+            Arc::new(AlertAdapter::new(Arc::new(MemoryFileAccessor::new(AlertList::new())))
+                .with_id_generator({
+                    let next = AtomicUsize::new(1);
+                    move || format!("a{:05}", next.fetch_add(1, Ordering::Relaxed))
+                }))
+        );
+        (event_manager, tx, gateway)
+    }
+
+    fn alarm(event_name: &str, text: &str) -> EventMessage {
+        let server_name = event_name.split('-').next().unwrap();
+        EventMessage {
+            event_name: event_name.to_string(),
+            server_name: server_name.to_string(),
+            text: text.to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_right_away_without_digest() {
+        let (mut event_manager, tx, gateway) = setup();
+
+        tx.send(alarm("main-down", "main is down")).await.unwrap();
+        assert!(event_manager.on_tick().await);
+
+        let sent = gateway.sent.lock().unwrap().clone();
+        assert_eq!(sent, vec![
+            (String::from("1"), String::from("main is down\nAlert: a00001")),
+            (String::from("2"), String::from("main is down\nAlert: a00001"))
+        ]);
+    }
+
+    #[tokio::test]
+    async fn digest_flushes_at_max_items_and_interval() {
+        let (event_manager, tx, gateway) = setup();
+        let mut event_manager = event_manager.with_digest(&DigestConfig { interval: 1, max_items: 2 });
+
+        tx.send(alarm("main-down", "main is down")).await.unwrap();
+        tx.send(alarm("db-down", "db is down")).await.unwrap();
+        assert!(event_manager.on_tick().await);
+        assert!(event_manager.on_tick().await);
+
+        // Chat 1 got both alarms, chat 2 waits for the interval
+        assert_eq!(gateway.sent.lock().unwrap().clone(), vec![
+            (String::from("1"), String::from("Digest: 2 alarms\nmain-down [a00001]: main is down\ndb-down [a00002]: db is down"))
+        ]);
+
+        assert!(event_manager.on_tick().await);
+        assert_eq!(gateway.sent.lock().unwrap()[1], (String::from("2"), String::from("main is down\nAlert: a00001")));
+
+        tx.send(alarm("main-down", "main is down again")).await.unwrap();
+        drop(tx);
+        assert!(event_manager.on_tick().await);
+        assert!(!event_manager.on_tick().await);
+        assert_eq!(gateway.sent.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn silenced_alarms_are_dropped_until_expiry() {
+        let silence = |id: &str, target: &str, kind, until| Silence {
+            id: id.to_string(),
+            target: target.to_string(),
+            kind,
+            until,
+            reason: Some(String::from("deploy")),
+            client_name: String::from("telegram"),
+            identity: String::from("2")
+        };
+        let mut silence_list = SilenceList::new();
+        silence_list.silences.push(silence("s1", "main", SilenceKind::Server, Utc::now() + TimeDelta::minutes(10)));
+        silence_list.silences.push(silence("s2", "db-down", SilenceKind::Event, Utc::now() - TimeDelta::seconds(1)));
+        let (mut event_manager, tx, gateway) = setup_with(silence_list, Config::new(None));
+
+        // Silenced by server name, while the expired silence of db-down no longer applies
+        tx.send(alarm("main-down", "main is down")).await.unwrap();
+        tx.send(alarm("db-down", "db is down")).await.unwrap();
+        assert!(event_manager.on_tick().await);
+        assert!(event_manager.on_tick().await);
+        assert_eq!(gateway.sent.lock().unwrap().clone(), vec![
+            (String::from("1"), String::from("db is down\nAlert: a00001"))
+        ]);
+
+        event_manager.expire_silences().await;
+        event_manager.expire_silences().await;
+        assert_eq!(gateway.sent.lock().unwrap()[1..], [
+            (String::from("2"), String::from("Silence expired\nAlarms of 'db-down' are sent again. (id: s2)"))
+        ]);
+    }
+
+    #[tokio::test]
+    async fn maintenance_suppresses_alarms_of_server() {
+        let mut config = Config::new(None);
+        config.maintenance_windows.push(MaintenanceConfig {
+            name: String::from("backup"),
+            server_name: String::from("main"),
+            schedule: Schedule { days: Vec::new(), time: (Local::now() - TimeDelta::minutes(1)).time() },
+            duration: 60 * 60
+        });
+        let (mut event_manager, tx, gateway) = setup_with(SilenceList::new(), config);
+
+        tx.send(alarm("main-down", "main is down")).await.unwrap();
+        tx.send(alarm("db-down", "db is down")).await.unwrap();
+        assert!(event_manager.on_tick().await);
+        assert!(event_manager.on_tick().await);
+        assert_eq!(gateway.sent.lock().unwrap().clone(), vec![
+            (String::from("1"), String::from("db is down\nAlert: a00001"))
+        ]);
+    }
+
+    struct BrokenSilenceUseCase;
+
+    #[async_trait]
+    impl SilenceUseCase for BrokenSilenceUseCase {
+        async fn silence(&self, _target: String, _kind: SilenceKind, _duration: Duration, _reason: Option<String>, _client_name: String, _identity: String) -> Result<Silence, Box<dyn Error + Send + Sync>> {
+            Err("broken".into())
+        }
+        async fn list_silences(&self) -> Result<Vec<Silence>, Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+        async fn unsilence(&self, _id: &str) -> Result<Silence, Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+        async fn find_silence(&self, _event_name: &str, _server_name: &str) -> Result<Option<Silence>, Box<dyn Error + Send + Sync>> {
+            Err("broken".into())
+        }
+        async fn take_expired(&self) -> Result<Vec<Silence>, Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+    }
+
+    struct BrokenAlertUseCase;
+
+    #[async_trait]
+    impl AlertUseCase for BrokenAlertUseCase {
+        async fn open_alert(&self, _event_name: String, _server_name: String, _text: String) -> Result<Alert, Box<dyn Error + Send + Sync>> {
+            Err("broken".into())
+        }
+        async fn find_alert(&self, _id: &str) -> Result<Option<Alert>, Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+        async fn acknowledge(&self, _id: &str, _acknowledged_by: String) -> Result<Alert, Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+        async fn list_unacknowledged(&self) -> Result<Vec<Alert>, Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+        async fn set_escalated(&self, _id: &str, _escalated: usize) -> Result<(), Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+        async fn prune(&self) -> Result<(), Box<dyn Error + Send + Sync>> { Err("broken".into()) }
+    }
+
+    #[tokio::test]
+    async fn alarm_is_sent_without_id_when_alert_cannot_be_opened() {
+        let (mut event_manager, tx, gateway) = setup();
+        event_manager.alert_use_case = Arc::new(BrokenAlertUseCase);
+
+        tx.send(alarm("main-down", "main is down")).await.unwrap();
+        assert!(event_manager.on_tick().await);
+        assert_eq!(gateway.sent.lock().unwrap().clone(), vec![
+            (String::from("1"), String::from("main is down")),
+            (String::from("2"), String::from("main is down"))
+        ]);
+    }
+
+    #[tokio::test]
+    async fn unreadable_silences_do_not_mute_alarms() {
+        let (mut event_manager, tx, gateway) = setup();
+        event_manager.silence_use_case = Arc::new(BrokenSilenceUseCase);
+
+        tx.send(alarm("db-down", "db is down")).await.unwrap();
+        assert!(event_manager.on_tick().await);
+        assert_eq!(gateway.sent.lock().unwrap().clone(), vec![
+            (String::from("1"), String::from("db is down\nAlert: a00001"))
+        ]);
+    }
 }
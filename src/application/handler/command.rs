@@ -1,17 +1,24 @@
 mod alarm;
+mod chat;
+mod silence;
 
 use std::error::Error;
 use anyhow::anyhow;
 use async_trait::async_trait;
-use log::{debug, trace};
+use chrono::{DateTime, Local};
+use log::{debug, error, trace};
 use crate::application::handler::command::alarm::AlarmCommand;
-use crate::application::handler::command::Command::{Alarm, EventList, HealthCheck, HealthCheckAll, Logs, Nothing};
+use crate::application::handler::command::chat::ChatCommand;
+use crate::application::handler::command::silence::SilenceCommand;
+use crate::application::handler::command::Command::{Alarm, EventList, HealthCheck, HealthCheckAll, Kill, Logs, Nothing, Restart, Silence};
 use crate::application::handler::GeneralHandler;
-use crate::domain::client::Message;
+use crate::domain::chat::{Chat, Role};
+use crate::domain::client::{Button, Message, Reply, Severity};
+use crate::domain::server::health::Health;
 
 #[async_trait]
 pub trait Run: Send + Sync {
-    async fn run(&self, handler: &mut GeneralHandler, id: String, message: &Message) -> Result<String, Box<dyn Error + Send + Sync>>;
+    async fn run(&self, handler: &mut GeneralHandler, chat: &Chat, message: &Message) -> Result<Reply, Box<dyn Error + Send + Sync>>;
 }
 
 #[derive(Debug)]
@@ -19,52 +26,175 @@ pub enum Command {
     Logs(String, i32),
     HealthCheckAll,
     HealthCheck(String),
+    Kill(String),
+    Restart(String),
     Nothing,
     Alarm(AlarmCommand),
+    Chat(ChatCommand),
+    Silence(SilenceCommand),
+    Ack(String),
     EventList,
 }
 
 #[async_trait]
 impl Run for Command {
-    async fn run(&self, handler: &mut GeneralHandler, id: String, message: &Message) -> Result<String, Box<dyn Error + Send + Sync>> {
+    async fn run(&self, handler: &mut GeneralHandler, chat: &Chat, message: &Message) -> Result<Reply, Box<dyn Error + Send + Sync>> {
         match self {
             Command::Logs(name, n) => {
                 handler.server_manager.logs(name.as_str(), *n).await
+                    .map(|logs| Reply::titled(format!("Logs: {name} (last {n} lines)")).with_code(logs))
                     .ok_or_else(|| anyhow!("Logs are not available."))
                     .map_err(Into::into)
             },
             Command::HealthCheck(name) => {
                 let health = handler.server_manager.healthcheck(name.as_str()).await;
-                let response = format!("===\nServer: {name}\n Health: {health}");
+                let maintenance = maintenance_until(handler, name.as_str()).await;
+                let response = Reply::titled(format!("Server: {name}"))
+                    .with_severity(severity_of(&health, &maintenance))
+                    .with_field("Health", health_text(&health, &maintenance));
                 Ok(response)
             },
             Command::HealthCheckAll => {
-                let response = handler.server_manager.healthcheck_all()
-                    .await
-                    .iter().map(|result|{format!("===\nServer: {}\nHealth: {}", result.0, result.1)})
-                    .collect::<Vec<String>>()
-                    .join("\n");
+                let mut results = Vec::new();
+                for (name, health) in handler.server_manager.healthcheck_all().await {
+                    if chat.can_access(name) {
+                        let maintenance = maintenance_until(handler, name).await;
+                        results.push((name, health, maintenance));
+                    }
+                }
+                let severity = results.iter()
+                    .map(|(_, health, maintenance)| severity_of(health, maintenance))
+                    .max()
+                    .unwrap_or_default();
+                let buttons = results.iter()
+                    .map(|(name, _, _)| vec![Button::new(name.to_string(), format!("/health {name}"))])
+                    .collect();
+                let response = results.iter()
+                    .fold(Reply::titled("Health"), |reply, (name, health, maintenance)| reply.with_field(*name, health_text(health, maintenance)))
+                    .with_severity(severity)
+                    .with_buttons(buttons);
+                Ok(response)
+            },
+            Command::Kill(name) => {
+                let response = if handler.server_manager.kill(name.as_str()).await {
+                    Reply::from(format!("Kill signal sent to '{name}'")).with_severity(Severity::Success)
+                } else {
+                    Reply::from(format!("Fail to kill '{name}'")).with_severity(Severity::Error)
+                };
+                Ok(response)
+            },
+            Command::Restart(name) => {
+                let response = if handler.server_manager.restart(name.as_str()).await {
+                    Reply::from(format!("'{name}' restarted")).with_severity(Severity::Success)
+                } else {
+                    Reply::from(format!("Fail to restart '{name}'")).with_severity(Severity::Error)
+                };
                 Ok(response)
             },
             Command::Alarm(command) => {
-                command.run(handler, id, message).await
+                command.run(handler, chat, message).await
+            },
+            Command::Chat(command) => {
+                command.run(handler, chat, message).await
+            },
+            Silence(command) => {
+                command.run(handler, chat, message).await
+            },
+            Command::Ack(id) => {
+                let alert = handler.alert_use_case.find_alert(id.as_str()).await?
+                    .ok_or_else(|| anyhow!("Alert '{id}' not found"))?;
+                // Chats the alert escalates to may ack it, even outside their server scope.
+                // Other alerts out of scope are reported as missing
+                let escalated_to_chat = handler.event_config_use_case.list_event().await?
+                    .iter()
+                    .filter(|event| event.name == alert.event_name)
+                    .flat_map(|event| event.escalation.iter())
+                    .any(|level| level.chat_ids.contains(&chat.id));
+                if !chat.can_access(alert.server_name.as_str()) && !escalated_to_chat {
+                    return Err(anyhow!("Alert '{id}' not found").into());
+                }
+                let alert = handler.alert_use_case.acknowledge(alert.id.as_str(), chat.describe()).await?;
+                Ok(Reply::from(format!("Alert {} of '{}' acknowledged.", alert.id, alert.event_name)).with_severity(Severity::Success))
             },
             Command::EventList => {
-                let events = handler.event_config_use_case.list_event().await?;
+                let events: Vec<_> = handler.event_config_use_case.list_event().await?
+                    .into_iter()
+                    .filter(|event| chat.can_access(event.target.as_str()))
+                    .collect();
                 let event_names = events.iter().map(|e| e.name.clone()).collect::<Vec<String>>().join("\n");
-                Ok(format!("Available events:\n{}", event_names))
+                Ok(Reply::titled("Available events").with_text(event_names))
             }
-            Command::Nothing => Ok(String::from(crate::application::handler::general::INVALID_COMMAND_MESSAGE))
+            Command::Nothing => Ok(crate::application::handler::general::INVALID_COMMAND_MESSAGE.into())
         }
     }
 }
 
+/// Servers in maintenance are expected to be unhealthy, so they do not raise the severity.
+fn severity_of(health: &Health, maintenance: &Option<DateTime<Local>>) -> Severity {
+    if maintenance.is_some() {
+        return Severity::Info;
+    }
+    match health {
+        Health::Healthy => Severity::Success,
+        Health::Unhealthy | Health::Down => Severity::Error,
+        Health::Deregistered | Health::Degraded | Health::Unknown(_) => Severity::Warning
+    }
+}
+
+/// Health is still reported when the maintenance windows cannot be read.
+async fn maintenance_until(handler: &GeneralHandler, server_name: &str) -> Option<DateTime<Local>> {
+    handler.maintenance_use_case.maintenance_until(server_name).await
+        .unwrap_or_else(|e| {
+            error!("[Command] Err: Fail to read maintenance windows: {e}");
+            None
+        })
+}
+
+fn health_text(health: &Health, maintenance: &Option<DateTime<Local>>) -> String {
+    match maintenance {
+        Some(until) => format!("{health} (in maintenance until {})", format_until(until, &Local::now())),
+        None => health.to_string()
+    }
+}
+
+/// Time of day, with the date once it is not today, as windows may last for days.
+pub fn format_until(until: &DateTime<Local>, now: &DateTime<Local>) -> String {
+    if until.date_naive() == now.date_naive() {
+        until.format("%H:%M").to_string()
+    } else {
+        until.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
 impl Command {
+    /// Commands which change a server's state and need a `/confirm` first.
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Kill(_) | Restart(_))
+    }
+
+    pub fn required_role(&self) -> Role {
+        match self {
+            Kill(_) | Restart(_) | Silence(SilenceCommand::Add(..) | SilenceCommand::Remove(_)) => Role::Operator,
+            Command::Chat(_) => Role::Admin,
+            Logs(..) | HealthCheckAll | HealthCheck(_) | Alarm(_) | Silence(SilenceCommand::List) | Command::Ack(_) | EventList | Nothing => Role::Viewer
+        }
+    }
+
+    /// Server the command touches, checked against the chat's server scope.
+    pub fn target_server(&self) -> Option<&str> {
+        match self {
+            Logs(name, _) | HealthCheck(name) | Kill(name) | Restart(name) => Some(name.as_str()),
+            HealthCheckAll | Alarm(_) | Command::Chat(_) | Silence(_) | Command::Ack(_) | EventList | Nothing => None
+        }
+    }
+
     pub fn parse(text: &str) -> Self {
         trace!("Command::parse(text: {})", &text);
         let command = match text.split_whitespace().collect::<Vec<_>>()[..] {
             ["/health", name] => HealthCheck(name.to_string()),
             ["/health"] => HealthCheckAll,
+            ["/kill", name] => Kill(name.to_string()),
+            ["/restart", name] => Restart(name.to_string()),
             ["/logs", name, n] => {
                 match n.parse() {
                     Ok(n) => Logs(name.to_string(), n),
@@ -83,6 +213,17 @@ impl Command {
             ["/alarm"] => {
                 Alarm(AlarmCommand::List)
             },
+            ["/chat", "revoke", id] => Command::Chat(ChatCommand::Revoke(id.to_string())),
+            ["/chat", "list"] => Command::Chat(ChatCommand::List),
+            ["/chat"] => Command::Chat(ChatCommand::List),
+            ["/silence", "list"] => Silence(SilenceCommand::List),
+            ["/silence"] => Silence(SilenceCommand::List),
+            ["/silence", target, duration, ref reason @ ..] => {
+                let reason = (!reason.is_empty()).then(|| reason.join(" "));
+                Silence(SilenceCommand::Add(target.to_string(), duration.to_string(), reason))
+            },
+            ["/unsilence", id] => Silence(SilenceCommand::Remove(id.to_string())),
+            ["/ack", id] => Command::Ack(id.to_string()),
             ["/event", "list"] => EventList,
             ["/event"] => EventList,
             _ => Nothing
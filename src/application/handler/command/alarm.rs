@@ -1,8 +1,10 @@
 use std::error::Error;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use crate::application::handler::command::Run;
 use crate::application::handler::GeneralHandler;
-use crate::domain::client::Message;
+use crate::domain::chat::Chat;
+use crate::domain::client::{Button, Message, Reply};
 use crate::domain::config::EventConfig;
 
 #[derive(Debug)]
@@ -14,30 +16,53 @@ pub enum AlarmCommand {
 
 #[async_trait]
 impl Run for AlarmCommand {
-    async fn run(&self, handler: &mut GeneralHandler, id: String, _message: &Message) -> Result<String, Box<dyn Error + Send + Sync>> {
+    async fn run(&self, handler: &mut GeneralHandler, chat: &Chat, _message: &Message) -> Result<Reply, Box<dyn Error + Send + Sync>> {
         match self {
             AlarmCommand::Add(event_name) => {
+                // Alarms reveal the target server's logs and health, so they follow the server scope
+                let accessible = handler.event_config_use_case.list_event().await?
+                    .iter()
+                    .any(|config| config.name == *event_name && chat.can_access(config.target.as_str()));
+                if !accessible {
+                    return Err(anyhow!("Event '{event_name}' is not available.").into());
+                }
                 let _ = handler.event_subscribe_use_case
-                    .subscribe(id, event_name.clone()).await?;
-                Ok(String::from("Successfully subscribed"))
+                    .subscribe(chat.id.clone(), event_name.clone()).await?;
+                Ok("Successfully subscribed".into())
             },
             AlarmCommand::Remove(event_name) => {
                 let _ = handler.event_subscribe_use_case
-                    .unsubscribe(id, event_name.clone()).await?;
-                Ok(String::from("Successfully removed"))
+                    .unsubscribe(chat.id.clone(), event_name.clone()).await?;
+                Ok("Successfully removed".into())
             },
             AlarmCommand::List => {
-                let response = handler.event_subscribe_use_case
-                    .list_subscribed_event(id).await?
+                let subscribed_events = handler.event_subscribe_use_case
+                    .list_subscribed_event(chat.id.clone()).await?;
+                let subscribed_names: Vec<String> = subscribed_events.iter()
+                    .map(|config| config.name.clone())
+                    .collect();
+                let response = subscribed_events
                     .into_iter()
-                    .map(|config: EventConfig| {format!("---\nname: {}\ntype: {}\ntarget: {}\nkeyword: {}",
-                        config.name,
-                        config.r#type,
-                        config.target,
-                        config.keyword)})
-                    .collect::<Vec<String>>()
-                    .join("\n\n");
-                Ok(format!("--- list ---\n{response}"))
+                    .fold(Reply::titled("Subscribed alarms"), |reply, config: EventConfig| {
+                        let description = if config.keyword.is_empty() {
+                            format!("{} on {}", config.r#type, config.target)
+                        } else {
+                            format!("{} on {} ({})", config.r#type, config.target, config.keyword)
+                        };
+                        reply.with_field(config.name, description)
+                    });
+
+                // One toggle per event, so a chat can (un)subscribe without typing the name
+                let buttons = handler.event_config_use_case.list_event().await?
+                    .into_iter()
+                    .filter(|config| chat.can_access(config.target.as_str()))
+                    .map(|config| if subscribed_names.contains(&config.name) {
+                        vec![Button::new(format!("Unsubscribe {}", config.name), format!("/alarm remove {}", config.name))]
+                    } else {
+                        vec![Button::new(format!("Subscribe {}", config.name), format!("/alarm add {}", config.name))]
+                    })
+                    .collect();
+                Ok(response.with_buttons(buttons))
             }
         }
     }
@@ -0,0 +1,35 @@
+use std::error::Error;
+use async_trait::async_trait;
+use crate::application::handler::command::Run;
+use crate::application::handler::GeneralHandler;
+use crate::domain::chat::Chat;
+use crate::domain::client::{Button, Message, Reply, Severity};
+
+#[derive(Debug)]
+pub enum ChatCommand {
+    List,
+    Revoke(String)
+}
+
+#[async_trait]
+impl Run for ChatCommand {
+    async fn run(&self, handler: &mut GeneralHandler, _chat: &Chat, _message: &Message) -> Result<Reply, Box<dyn Error + Send + Sync>> {
+        match self {
+            ChatCommand::List => {
+                let chats = handler.auth_use_case.list_chats().await?;
+                let buttons = chats.iter()
+                    .map(|chat| vec![Button::new(format!("Revoke {}", chat.describe()), format!("/chat revoke {}", chat.id))])
+                    .collect();
+                let response = chats.into_iter()
+                    .fold(Reply::titled("Registered chats"), |reply, chat| reply.with_field(chat.id.clone(), chat.describe()))
+                    .with_buttons(buttons);
+                Ok(response)
+            },
+            ChatCommand::Revoke(chat_id) => {
+                let revoked = handler.auth_use_case.revoke(chat_id.as_str()).await?;
+                handler.event_subscribe_use_case.unsubscribe_all(revoked.id.clone()).await?;
+                Ok(Reply::from(format!("Chat revoked: {}", revoked.describe())).with_severity(Severity::Success))
+            }
+        }
+    }
+}
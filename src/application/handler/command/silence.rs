@@ -0,0 +1,88 @@
+use std::error::Error;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::Utc;
+use crate::application::handler::command::Run;
+use crate::application::handler::GeneralHandler;
+use crate::domain::chat::Chat;
+use crate::domain::client::{Button, Message, Reply, Severity};
+use crate::domain::event::{format_duration, parse_duration};
+use crate::domain::silence::{SilenceKind, MAX_DURATION};
+
+#[derive(Debug)]
+pub enum SilenceCommand {
+    /// Event or server name, how long (e.g. 30m), and an optional reason
+    Add(String, String, Option<String>),
+    Remove(String),
+    List
+}
+
+#[async_trait]
+impl Run for SilenceCommand {
+    async fn run(&self, handler: &mut GeneralHandler, chat: &Chat, message: &Message) -> Result<Reply, Box<dyn Error + Send + Sync>> {
+        match self {
+            SilenceCommand::Add(target, duration, reason) => {
+                let duration = parse_duration(duration.as_str())
+                    .filter(|duration| *duration <= MAX_DURATION)
+                    .ok_or_else(|| anyhow!("Invalid duration '{duration}'. Use e.g. 30m, 2h or 1h30m, up to 30d."))?;
+                let kind = accessible_kind(handler, chat, target).await?
+                    .ok_or_else(|| anyhow!("No event or server named '{target}' is available."))?;
+                let silence = handler.silence_use_case.silence(
+                    target.clone(), kind, duration, reason.clone(), message.client_name.clone(), message.chat_id.clone()
+                ).await?;
+                let response = Reply::from(format!("Alarms of '{target}' are silenced for {}. (id: {})", format_duration(duration), silence.id))
+                    .with_severity(Severity::Success)
+                    .with_buttons(vec![vec![Button::new(String::from("Unsilence"), format!("/unsilence {}", silence.id))]]);
+                Ok(response)
+            },
+            SilenceCommand::Remove(id) => {
+                let silence = handler.silence_use_case.list_silences().await?
+                    .into_iter()
+                    .find(|silence| silence.id == *id)
+                    .ok_or_else(|| anyhow!("Silence '{id}' not found"))?;
+                if !is_accessible(handler, chat, silence.target.as_str(), silence.kind).await? {
+                    return Err(anyhow!("Silence '{id}' not found").into());
+                }
+                let silence = handler.silence_use_case.unsilence(id.as_str()).await?;
+                Ok(Reply::from(format!("Alarms of '{}' are sent again.", silence.target)).with_severity(Severity::Success))
+            },
+            SilenceCommand::List => {
+                let now = Utc::now();
+                let mut silences = Vec::new();
+                for silence in handler.silence_use_case.list_silences().await? {
+                    if is_accessible(handler, chat, silence.target.as_str(), silence.kind).await? {
+                        silences.push(silence);
+                    }
+                }
+                let buttons = silences.iter()
+                    .map(|silence| vec![Button::new(format!("Unsilence {}", silence.target), format!("/unsilence {}", silence.id))])
+                    .collect();
+                let response = silences.iter()
+                    .fold(Reply::titled("Active silences"), |reply, silence| reply.with_field(silence.id.clone(), silence.describe(now)))
+                    .with_buttons(buttons);
+                Ok(response)
+            }
+        }
+    }
+}
+
+/// What the target names for the chat, preferring an event over a server of the same name.
+async fn accessible_kind(handler: &GeneralHandler, chat: &Chat, target: &str) -> Result<Option<SilenceKind>, Box<dyn Error + Send + Sync>> {
+    for kind in [SilenceKind::Event, SilenceKind::Server] {
+        if is_accessible(handler, chat, target, kind).await? {
+            return Ok(Some(kind));
+        }
+    }
+    Ok(None)
+}
+
+/// Silences follow the server scope, like alarms: the target is an event or a server with events the chat can access.
+async fn is_accessible(handler: &GeneralHandler, chat: &Chat, target: &str, kind: SilenceKind) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    Ok(handler.event_config_use_case.list_event().await?
+        .iter()
+        .filter(|config| chat.can_access(config.target.as_str()))
+        .any(|config| match kind {
+            SilenceKind::Event => config.name == target,
+            SilenceKind::Server => config.target == target
+        }))
+}
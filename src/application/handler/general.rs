@@ -1,12 +1,20 @@
+use std::collections::HashMap;
+use std::time::Duration;
 use async_trait::async_trait;
 use derive_new::new;
-use log::{debug, trace};
+use log::{debug, error, trace};
+use tokio::time::Instant;
+use uuid::Uuid;
+use crate::application::audit::AuditLogger;
 use crate::application::client::MessageGateway;
-use crate::application::config::{AuthUseCase, EventConfigUseCase, EventSubscribeUseCase};
+use crate::application::config::{AlertUseCase, AuthUseCase, EventConfigUseCase, EventSubscribeUseCase, LockoutUseCase, MaintenanceUseCase, SilenceUseCase};
 use crate::application::handler::command::{Command, Run};
 use crate::application::handler::MessageHandler;
 use crate::application::server::ServerManager;
-use crate::domain::client::Message;
+use crate::domain::audit::{AuditEntry, AuditOutcome};
+use crate::domain::chat::{Chat, Role};
+use crate::domain::client::{Button, Message, Reply, Severity};
+use crate::domain::lockout::{Lockout, LockoutScope};
 
 pub const INVALID_COMMAND_MESSAGE: &str = r#"Invalid or unknown command.
 
@@ -16,53 +24,84 @@ Available commands:
   Example: /logs main 100
 
 - /health (server_name)
-  (server_name): optional. If provided, returns the health status of the specified server."#;
+  (server_name): optional. If provided, returns the health status of the specified server.
+
+- /kill <server_name>
+  Sends the kill request to the specified server.
+
+- /restart <server_name>
+  Restarts the specified server with its configured restart method.
+
+- /silence <event_name|server_name> <duration> (reason)
+  Mutes alarms for a while, e.g. /silence main 30m deploy. /silence list shows active silences.
+
+- /unsilence <id>
+  Ends a silence early.
+
+- /ack <alert_id>
+  Acknowledges an alarm, which stops its escalation.
+
+- /chat (revoke <chat_id>)
+  Admin only. Lists registered chats, or revokes one with its alarms.
+
+/kill and /restart only run after /confirm <code> (or /cancel)."#;
+
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
 
 use std::sync::Arc;
 
 #[derive(new)]
+#[allow(clippy::too_many_arguments)]
 pub struct GeneralHandler {
     pub message_gateway: Arc<dyn MessageGateway>,
     pub server_manager: Arc<dyn ServerManager>,
     pub auth_use_case: Box<dyn AuthUseCase>,
+    pub lockout_use_case: Box<dyn LockoutUseCase>,
     pub event_subscribe_use_case: Arc<dyn EventSubscribeUseCase>,
     pub event_config_use_case: Arc<dyn EventConfigUseCase>,
+    pub silence_use_case: Arc<dyn SilenceUseCase>,
+    pub maintenance_use_case: Arc<dyn MaintenanceUseCase>,
+    pub alert_use_case: Arc<dyn AlertUseCase>,
+    pub audit_logger: Arc<dyn AuditLogger>,
+    /// Destructive commands waiting for `/confirm`, by the id of the chat which issued them
+    #[new(default)]
+    pending_confirmations: HashMap<String, PendingConfirmation>,
+}
+
+struct PendingConfirmation {
+    code: String,
+    command: Command,
+    expires_at: Instant
 }
 
 #[async_trait]
 impl MessageHandler for GeneralHandler {
     async fn handle(&mut self, message: Message) {
         match message.data.split_whitespace().collect::<Vec<_>>()[..] {
-            ["/register", password] => {
-                let response = if !self.auth_use_case.password_required() {
-                        String::from("Password is not required")
-                } else if self.auth_use_case.validate_password(password.to_string()).await {
-                    match self.auth_use_case.register(message.client_name.clone(), message.chat_id.clone()).await {
-                        Ok(_) => String::from("Successfully registered."),
-                        Err(e) => format!("Fail to register: {e}")
-                    }
-
-                } else {
-                    String::from("Invalid password. Usage: /register <password>")
-                };
+            ["/register", password, ref code @ ..] if code.len() <= 1 => {
+                let (response, outcome, registered_chat_id) = self.register(&message, password, code.first().copied()).await;
+                self.audit(&message, registered_chat_id, String::from("Register"), outcome, &response).await;
                 self.message_gateway.send_message(
                     message.client_name.as_str(),
                     message.chat_id.as_str(),
-                    response.as_str()
+                    response
                 )
                     .await
             },
             _ => {
-                let auth_id = self.auth_use_case
+                let chat = self.auth_use_case
                     .authenticate(message.client_name.clone(), message.chat_id.clone())
                     .await;
-                if let Some(id) = auth_id {
-                    self._handle(String::from(id), message).await
+                if let Some(chat) = chat {
+                    self._handle(chat, message).await
                 } else {
+                    let response = Reply::from(format!("Registration required. Usage: {}", self.register_usage()));
+                    let command = format!("{:?}", Command::parse(message.data.as_str()));
+                    self.audit(&message, None, command, AuditOutcome::Unauthenticated, &response).await;
                     self.message_gateway.send_message(
                         message.client_name.as_str(),
                         message.chat_id.as_str(),
-                        "Registration required. Usage: /register <password>"
+                        response
                     )
                         .await
                 }
@@ -72,24 +111,631 @@ impl MessageHandler for GeneralHandler {
 }
 impl GeneralHandler {
 
-    async fn _handle(&mut self, id: String, message: Message) {
+    fn register_usage(&self) -> &'static str {
+        if self.auth_use_case.totp_required() {
+            "/register <password> <code>"
+        } else {
+            "/register <password>"
+        }
+    }
+
+    /// Returns the id of the registered chat as well, for the audit log.
+    async fn register(&mut self, message: &Message, password: &str, code: Option<&str>) -> (Reply, AuditOutcome, Option<String>) {
+        if !self.auth_use_case.password_required() {
+            return ("Password is not required".into(), AuditOutcome::Failure, None);
+        }
+
+        let client_name = message.client_name.as_str();
+        let identity = message.chat_id.as_str();
+        // Nothing is checked while locked, so switching chats gains no guesses. `sw password unlock` lifts it
+        match self.lockout_use_case.locked_for(client_name, identity).await {
+            Ok(Some(seconds)) => return (
+                Reply::from(format!("Too many failed attempts. Try again in {seconds} seconds.")).with_severity(Severity::Warning),
+                AuditOutcome::Denied,
+                None
+            ),
+            Ok(None) => {},
+            Err(e) => return (Reply::from(format!("Fail to register: {e}")).with_severity(Severity::Error), AuditOutcome::Failure, None)
+        }
+
+        let valid = self.auth_use_case.validate_password(password.to_string()).await
+            && (!self.auth_use_case.totp_required() || match code {
+                Some(code) => self.auth_use_case.validate_totp(code).await,
+                None => false
+            });
+        if !valid {
+            match self.lockout_use_case.record_failure(client_name, identity).await {
+                Ok(Some(lockout)) => self.notify_admins(message, &lockout).await,
+                Ok(None) => {},
+                Err(e) => error!("[GeneralHandler] Err: Fail to record failed attempt: {e}")
+            }
+            let response = if self.auth_use_case.totp_required() {
+                format!("Invalid password or code. Usage: {}", self.register_usage())
+            } else {
+                format!("Invalid password. Usage: {}", self.register_usage())
+            };
+            return (response.into(), AuditOutcome::Failure, None);
+        }
+
+        if let Err(e) = self.lockout_use_case.record_success(client_name, identity).await {
+            error!("[GeneralHandler] Err: Fail to reset failed attempts: {e}");
+        }
+        match self.auth_use_case.register(message.client_name.clone(), message.chat_id.clone()).await {
+            Ok(id) => (format!("Successfully registered. Chat id: {id}").into(), AuditOutcome::Success, Some(id)),
+            Err(e) => (format!("Fail to register: {e}").into(), AuditOutcome::Failure, None)
+        }
+    }
+
+    async fn audit(&self, message: &Message, registered_chat_id: Option<String>, command: String, outcome: AuditOutcome, response: &Reply) {
+        let detail = response.title.clone()
+            .or_else(|| response.text.lines().next().map(str::to_string));
+        let entry = AuditEntry::new(
+            message.client_name.clone(), message.chat_id.clone(), registered_chat_id, command, outcome, detail
+        );
+        if let Err(e) = self.audit_logger.record(&entry).await {
+            error!("[GeneralHandler] Err: Fail to write audit log: {e}");
+        }
+    }
+
+    async fn notify_admins(&self, message: &Message, lockout: &Lockout) {
+        let text = match lockout.scope {
+            LockoutScope::Chat => format!(
+                "{} failed /register attempts from {}:{}. The chat is locked for {} seconds.",
+                lockout.failures, message.client_name, message.chat_id, lockout.seconds
+            ),
+            LockoutScope::Global => format!(
+                "{} failed /register attempts across all chats, the last from {}:{}. Registration is locked for {} seconds.",
+                lockout.failures, message.client_name, message.chat_id, lockout.seconds
+            )
+        };
+
+        let chats = match self.auth_use_case.list_chats().await {
+            Ok(chats) => chats,
+            Err(e) => {
+                error!("[GeneralHandler] Err: Fail to notify admins: {e}");
+                return;
+            }
+        };
+        for chat in chats.iter().filter(|chat| chat.has_role(Role::Admin)) {
+            let reply = Reply::titled("Registration locked")
+                .with_severity(Severity::Warning)
+                .with_text(text.clone());
+            self.message_gateway.send_message(chat.client_name.as_str(), chat.identity.as_str(), reply).await;
+        }
+    }
+
+    async fn _handle(&mut self, chat: Chat, message: Message) {
         trace!("GeneralHandler::handle");
         debug!("handling message: {:?}", &message);
 
-        let command = Command::parse(message.data.as_str());
-        debug!("parsed command: {:?}", &command);
+        // The audit log names the command which actually ran, e.g. the confirmed one for /confirm
+        let (label, outcome, response) = match message.data.split_whitespace().collect::<Vec<_>>()[..] {
+            ["/confirm", code] => {
+                let label = match self.pending_confirmations.get(&chat.id) {
+                    Some(pending) => format!("Confirm({:?})", pending.command),
+                    None => String::from("Confirm")
+                };
+                (label, None, self.confirm(&chat, code, &message).await)
+            },
+            ["/cancel"] => (String::from("Cancel"), None, Ok(self.cancel(chat.id.as_str()))),
+            _ => {
+                let command = Command::parse(message.data.as_str());
+                debug!("parsed command: {:?}", &command);
+                let label = format!("{:?}", command);
 
-        let response = command.run(self, id, &message).await;
+                if let Err(reason) = Self::check_permission(&chat, &command) {
+                    (label, Some(AuditOutcome::Denied), Ok(Reply::from(reason).with_severity(Severity::Error)))
+                } else if command.is_destructive() {
+                    (label, Some(AuditOutcome::Pending), Ok(self.request_confirmation(chat.id.clone(), command, &message)))
+                } else {
+                    (label, None, command.run(self, &chat, &message).await)
+                }
+            }
+        };
         debug!("response: {:?}", &response);
 
-        let response = response.unwrap_or_else(|e| format!("[Err] {e}"));
+        let response = response.unwrap_or_else(|e| Reply::from(e.to_string()).with_severity(Severity::Error));
+        let outcome = outcome.unwrap_or(match response.severity {
+            Severity::Error => AuditOutcome::Failure,
+            Severity::Info | Severity::Success | Severity::Warning => AuditOutcome::Success
+        });
+        self.audit(&message, Some(chat.id.clone()), label, outcome, &response).await;
 
         self.message_gateway
             .send_message(
                 message.client_name.as_str(),
                 message.chat_id.as_str(),
-                response.as_str()
+                response
             )
             .await;
     }
-}
\ No newline at end of file
+
+    fn check_permission(chat: &Chat, command: &Command) -> Result<(), String> {
+        let required_role = command.required_role();
+        if !chat.has_role(required_role) {
+            return Err(format!("Permission denied: {required_role} role is required."));
+        }
+        match command.target_server() {
+            Some(server_name) if !chat.can_access(server_name) => {
+                Err(format!("Permission denied: no access to server '{server_name}'."))
+            },
+            _ => Ok(())
+        }
+    }
+
+    fn request_confirmation(&mut self, id: String, command: Command, message: &Message) -> Reply {
+        // A new request replaces the previous one of the chat
+        let code = format!("{:06}", Uuid::new_v4().as_u128() % 1_000_000);
+        let reply = Reply::titled(format!("Confirm: {}", message.data.trim()))
+            .with_severity(Severity::Warning)
+            .with_text(format!("Send /confirm {code} within {} seconds, or /cancel.", CONFIRMATION_TIMEOUT.as_secs()))
+            .with_buttons(vec![vec![
+                Button::new(String::from("Confirm"), format!("/confirm {code}")),
+                Button::new(String::from("Cancel"), String::from("/cancel"))
+            ]]);
+
+        self.pending_confirmations.insert(id, PendingConfirmation {
+            code,
+            command,
+            expires_at: Instant::now() + CONFIRMATION_TIMEOUT
+        });
+        reply
+    }
+
+    async fn confirm(&mut self, chat: &Chat, code: &str, message: &Message) -> Result<Reply, Box<dyn std::error::Error + Send + Sync>> {
+        // The code is single use, so a wrong guess also cancels the command
+        let pending = match self.pending_confirmations.remove(&chat.id) {
+            Some(pending) => pending,
+            None => return Ok(Reply::from("No command is waiting for confirmation."))
+        };
+
+        if Instant::now() >= pending.expires_at {
+            return Ok(Reply::from("Confirmation code expired. Please send the command again.").with_severity(Severity::Error));
+        }
+        if pending.code != code {
+            return Ok(Reply::from("Invalid confirmation code. The command is cancelled.").with_severity(Severity::Error));
+        }
+        pending.command.run(self, chat, message).await
+    }
+
+    fn cancel(&mut self, id: &str) -> Reply {
+        match self.pending_confirmations.remove(id) {
+            Some(_) => Reply::from("Cancelled."),
+            None => Reply::from("No command is waiting for confirmation.")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tokio::io::{duplex, AsyncBufReadExt, BufReader, DuplexStream, Lines};
+    use tokio::sync::mpsc;
+    use tokio::sync::mpsc::Receiver;
+    use tokio_stream::Stream;
+    use super::*;
+    use crate::application::handler::command::format_until;
+    use crate::application::server::ServerManager;
+    use crate::application::worker::{Worker, WorkerRunner};
+    use crate::domain::chat::{ChatList, Role};
+    use crate::domain::config::{Config, EventConfig, EventSubscribeList, MatchMode};
+    use crate::domain::server::health::Health;
+    use crate::infrastructure::client::console::{ConsoleClient, STDIN_CHAT_ID};
+    use crate::infrastructure::client::{Client, ClientManager, MessageAdapter};
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+    use crate::infrastructure::config::auth::AuthAdapter;
+    use crate::infrastructure::config::{AlertAdapter, EventConfigAdapter, LockoutAdapter, MaintenanceAdapter, SilenceAdapter};
+    use crate::domain::alert::AlertList;
+    use crate::domain::silence::SilenceList;
+    use crate::domain::config::{EscalationLevel, MaintenanceConfig, Schedule};
+    use chrono::Local;
+    use crate::domain::lockout::LockoutState;
+    use crate::domain::totp::TotpState;
+    use crate::infrastructure::common::totp;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    struct StubServerManager;
+
+    #[async_trait]
+    impl ServerManager for StubServerManager {
+        async fn kill(&self, _name: &str) -> bool { false }
+        async fn restart(&self, _name: &str) -> bool { true }
+        async fn healthcheck(&self, _name: &str) -> Health { Health::Healthy }
+        async fn healthcheck_all(&self) -> Vec<(&str, Health)> { vec![("main", Health::Healthy)] }
+        async fn logs(&self, _name: &str, _n: i32) -> Option<String> { None }
+        async fn logs_stream(&self, _name: &str) -> Option<Box<dyn Stream<Item=String> + Send>> { None }
+    }
+
+    #[derive(Default)]
+    struct MemoryAuditLogger {
+        entries: Mutex<Vec<AuditEntry>>
+    }
+
+    #[async_trait]
+    impl AuditLogger for MemoryAuditLogger {
+        async fn record(&self, entry: &AuditEntry) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.entries.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+
+        async fn entries(&self) -> Result<Vec<AuditEntry>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+    }
+
+    /// Handler replying through a console client which reads `input`, with the lines it writes back.
+    async fn setup(input: &'static [u8]) -> (GeneralHandler, Receiver<Message>, Lines<BufReader<DuplexStream>>) {
+        let (output, written) = duplex(4096);
+        let mut console = ConsoleClient::with_io("console".to_string(), Box::new(input), Box::new(output));
+        let (tx, rx) = mpsc::channel(16);
+        console.subscribe(tx);
+        console.on_tick().await;
+
+        let mut config = Config::new(Some(String::from("secret")));
+        config.events.push(EventConfig {
+            r#type: String::from("logs"),
+            name: String::from("main-error"),
+            target: String::from("main"),
+            keyword: String::from("ERROR"),
+            match_mode: MatchMode::Substring,
+            negate: false,
+            message: None,
+            cooldown: None,
+            dedup_window: None,
+            escalation: Vec::new()
+        });
+        let config_file_accessor = Arc::new(MemoryFileAccessor::new(config));
+        let chat_list_file_accessor = Arc::new(MemoryFileAccessor::new(ChatList::new()));
+        let subscribe_file_accessor = Arc::new(MemoryFileAccessor::new(EventSubscribeList::new()));
+
+        let clients: HashMap<String, Box<dyn Client>> = HashMap::from([(String::from("console"), Box::new(console) as Box<dyn Client>)]);
+        let client_manager = ClientManager::new(
+            Arc::new(Mutex::new(WorkerRunner::new())),
+            Arc::new(Mutex::new(clients)),
+            config_file_accessor.clone()
+        );
+        let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor, Arc::new(MemoryFileAccessor::new(TotpState::new())));
+        auth_adapter.init().await;
+        let event_config_adapter = Arc::new(EventConfigAdapter::new(config_file_accessor.clone(), subscribe_file_accessor));
+
+        let handler = GeneralHandler::new(
+            Arc::new(MessageAdapter::new(Arc::new(client_manager))),
+            Arc::new(StubServerManager),
+            Box::new(auth_adapter),
+            Box::new(LockoutAdapter::new(Arc::new(MemoryFileAccessor::new(LockoutState::new())))),
+            event_config_adapter.clone(),
+            event_config_adapter,
+            Arc::new(SilenceAdapter::new(Arc::new(MemoryFileAccessor::new(SilenceList::new())))),
+            Arc::new(MaintenanceAdapter::new(config_file_accessor)),
+            Arc::new(AlertAdapter::new(Arc::new(MemoryFileAccessor::new(AlertList::new())))),
+            Arc::new(MemoryAuditLogger::default())
+        );
+        (handler, rx, BufReader::new(written).lines())
+    }
+
+    fn console_message(data: &str) -> Message {
+        Message::new(String::from("console"), String::from(STDIN_CHAT_ID), data.to_string())
+    }
+
+    #[tokio::test]
+    async fn handle_console_commands() {
+        let (mut handler, mut rx, mut lines) = setup(b"/health\n/register wrong\n/register secret\n/event\n/health main\n/logs main 10\n").await;
+
+        while let Ok(message) = rx.try_recv() {
+            assert_eq!(message.chat_id, STDIN_CHAT_ID);
+            handler.handle(message).await;
+        }
+
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+        assert_eq!(next_line().await, "Registration required. Usage: /register <password>");
+        assert_eq!(next_line().await, "Invalid password. Usage: /register <password>");
+        assert!(next_line().await.starts_with("Successfully registered. Chat id: "));
+        assert_eq!(next_line().await, "Available events");
+        assert_eq!(next_line().await, "main-error");
+        assert_eq!(next_line().await, "Server: main");
+        assert_eq!(next_line().await, "Health: Healthy");
+        assert_eq!(next_line().await, "[ERROR] Logs are not available.");
+    }
+
+    #[tokio::test]
+    async fn destructive_commands_require_confirmation() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+        handler.auth_use_case.set_role(chat_id.as_str(), Role::Operator).await.unwrap();
+
+        // Wrong code cancels the command
+        handler.handle(console_message("/restart main")).await;
+        assert_eq!(next_line().await, "[WARNING] Confirm: /restart main");
+        next_line().await;
+        handler.handle(console_message("/confirm wrong")).await;
+        assert_eq!(next_line().await, "[ERROR] Invalid confirmation code. The command is cancelled.");
+        handler.handle(console_message("/cancel")).await;
+        assert_eq!(next_line().await, "No command is waiting for confirmation.");
+
+        handler.handle(console_message("/restart main")).await;
+        next_line().await;
+        let prompt = next_line().await;
+        let code = prompt.split_whitespace().nth(2).unwrap().to_string();
+        assert_eq!(prompt, format!("Send /confirm {code} within 60 seconds, or /cancel."));
+        handler.handle(console_message(format!("/confirm {code}").as_str())).await;
+        assert_eq!(next_line().await, "'main' restarted");
+
+        // Codes are single use and expire
+        handler.handle(console_message(format!("/confirm {code}").as_str())).await;
+        assert_eq!(next_line().await, "No command is waiting for confirmation.");
+        handler.handle(console_message("/kill main")).await;
+        next_line().await;
+        let code = next_line().await.split_whitespace().nth(2).unwrap().to_string();
+        for pending in handler.pending_confirmations.values_mut() {
+            pending.expires_at = Instant::now();
+        }
+        handler.handle(console_message(format!("/confirm {code}").as_str())).await;
+        assert_eq!(next_line().await, "[ERROR] Confirmation code expired. Please send the command again.");
+    }
+
+    #[tokio::test]
+    async fn commands_follow_role_and_server_scope() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+
+        // New chats are viewers
+        handler.handle(console_message("/restart main")).await;
+        assert_eq!(next_line().await, "[ERROR] Permission denied: operator role is required.");
+        handler.handle(console_message("/health main")).await;
+        assert_eq!(next_line().await, "Server: main");
+        next_line().await;
+
+        handler.auth_use_case.set_role(chat_id.as_str(), Role::Operator).await.unwrap();
+        handler.auth_use_case.set_servers(chat_id.as_str(), Some(vec![String::from("other")])).await.unwrap();
+        handler.handle(console_message("/restart main")).await;
+        assert_eq!(next_line().await, "[ERROR] Permission denied: no access to server 'main'.");
+        handler.handle(console_message("/alarm add main-error")).await;
+        assert_eq!(next_line().await, "[ERROR] Event 'main-error' is not available.");
+        handler.handle(console_message("/health")).await;
+        assert_eq!(next_line().await, "Health");
+        handler.handle(console_message("/event")).await;
+        assert_eq!(next_line().await, "Available events");
+    }
+
+    #[tokio::test]
+    async fn register_locks_out_after_failures() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+        handler.auth_use_case.set_role(chat_id.as_str(), Role::Admin).await.unwrap();
+
+        let attacker = |data: &str| Message::new(String::from("console"), String::from("other"), data.to_string());
+        for _ in 0..3 {
+            handler.handle(attacker("/register wrong")).await;
+        }
+        assert_eq!(next_line().await, "[WARNING] Registration locked");
+        assert_eq!(next_line().await, "3 failed /register attempts from console:other. The chat is locked for 30 seconds.");
+
+        // Even the right password is refused while locked, and the chat stays unregistered
+        handler.handle(attacker("/register secret")).await;
+        assert!(handler.lockout_use_case.locked_for("console", "other").await.unwrap().is_some());
+        assert!(handler.auth_use_case.authenticate(String::from("console"), String::from("other")).await.is_none());
+        assert!(handler.lockout_use_case.locked_for("console", STDIN_CHAT_ID).await.unwrap().is_none());
+
+        // Guessing spread over chats locks registration, and admins hear of it once
+        let from = |identity: String, data: &str| Message::new(String::from("console"), identity, data.to_string());
+        for i in 0..8 {
+            handler.handle(from(format!("guess-{i}"), "/register wrong")).await;
+        }
+        assert_eq!(next_line().await, "[WARNING] Registration locked");
+        assert_eq!(next_line().await, "10 failed /register attempts across all chats, the last from console:guess-6. Registration is locked for 60 seconds.");
+        assert!(handler.lockout_use_case.locked_for("console", "late").await.unwrap().is_some());
+
+        // Every chat is refused without checking the password, until lifted from the CLI
+        handler.handle(from(String::from("late"), "/register secret")).await;
+        assert!(handler.auth_use_case.authenticate(String::from("console"), String::from("late")).await.is_none());
+        handler.handle(console_message("/register secret")).await;
+        assert!(next_line().await.starts_with("[WARNING] Too many failed attempts. Try again in "));
+        handler.lockout_use_case.reset().await.unwrap();
+        handler.handle(from(String::from("late"), "/register secret")).await;
+        assert!(handler.auth_use_case.authenticate(String::from("console"), String::from("late")).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn admin_revokes_chat() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+        handler.handle(console_message("/chat")).await;
+        assert_eq!(next_line().await, "[ERROR] Permission denied: admin role is required.");
+        handler.auth_use_case.set_role(chat_id.as_str(), Role::Admin).await.unwrap();
+
+        let other_id = handler.auth_use_case.register(String::from("console"), String::from("other")).await.unwrap();
+        handler.event_subscribe_use_case.subscribe(other_id.clone(), String::from("main-error")).await.unwrap();
+
+        handler.handle(console_message("/chat")).await;
+        assert_eq!(next_line().await, "Registered chats");
+        assert_eq!(next_line().await, format!("{chat_id}: console:stdin (admin, servers: all)"));
+        assert_eq!(next_line().await, format!("{other_id}: console:other (viewer, servers: all)"));
+
+        handler.handle(console_message(format!("/chat revoke {other_id}").as_str())).await;
+        assert_eq!(next_line().await, "Chat revoked: console:other (viewer, servers: all)");
+        assert!(handler.auth_use_case.authenticate(String::from("console"), String::from("other")).await.is_none());
+        assert!(handler.event_subscribe_use_case.list_subscribed_event(other_id.clone()).await.unwrap().is_empty());
+
+        handler.handle(console_message(format!("/chat revoke {other_id}").as_str())).await;
+        assert_eq!(next_line().await, format!("[ERROR] Chat '{other_id}' is not registered"));
+    }
+
+    #[tokio::test]
+    async fn operator_silences_alarms() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+        handler.handle(console_message("/silence main 30m deploy")).await;
+        assert_eq!(next_line().await, "[ERROR] Permission denied: operator role is required.");
+        handler.auth_use_case.set_role(chat_id.as_str(), Role::Operator).await.unwrap();
+
+        handler.handle(console_message("/silence main 100000000d")).await;
+        assert_eq!(next_line().await, "[ERROR] Invalid duration '100000000d'. Use e.g. 30m, 2h or 1h30m, up to 30d.");
+        assert!(handler.silence_use_case.list_silences().await.unwrap().is_empty());
+        handler.handle(console_message("/silence db 30m")).await;
+        assert_eq!(next_line().await, "[ERROR] No event or server named 'db' is available.");
+        handler.handle(console_message("/silence main 30m deploy v2")).await;
+        let line = next_line().await;
+        assert!(line.starts_with("Alarms of 'main' are silenced for 30m 0s. (id: "), "{line}");
+
+        let silence = handler.silence_use_case.find_silence("main-error", "main").await.unwrap().unwrap();
+        assert_eq!(silence.reason.as_deref(), Some("deploy v2"));
+        assert_eq!(silence.identity, STDIN_CHAT_ID);
+
+        handler.handle(console_message("/silence list")).await;
+        assert_eq!(next_line().await, "Active silences");
+        assert!(next_line().await.starts_with(format!("{}: main for ", silence.id).as_str()));
+
+        handler.handle(console_message(format!("/unsilence {}", silence.id).as_str())).await;
+        assert_eq!(next_line().await, "Alarms of 'main' are sent again.");
+        assert!(handler.silence_use_case.find_silence("main-error", "main").await.unwrap().is_none());
+
+        // An event named like a server out of scope silences only the event
+        let mut event = handler.event_config_use_case.list_event().await.unwrap().remove(0);
+        event.name = String::from("db");
+        handler.event_config_use_case.add_event(event).await.unwrap();
+        handler.auth_use_case.set_servers(chat_id.as_str(), Some(vec![String::from("main")])).await.unwrap();
+        handler.handle(console_message("/silence db 30m")).await;
+        assert!(next_line().await.starts_with("Alarms of 'db' are silenced for 30m 0s. (id: "));
+        assert!(handler.silence_use_case.find_silence("db", "main").await.unwrap().is_some());
+        assert!(handler.silence_use_case.find_silence("db-down", "db").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn health_shows_maintenance() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        next_line().await;
+        let start = Local::now() - chrono::TimeDelta::minutes(1);
+        handler.maintenance_use_case.add_maintenance(MaintenanceConfig {
+            name: String::from("backup"),
+            server_name: String::from("main"),
+            schedule: Schedule { days: Vec::new(), time: start.time() },
+            duration: 60 * 60
+        }).await.unwrap();
+        let until = format_until(&(start + chrono::TimeDelta::hours(1)), &Local::now());
+
+        handler.handle(console_message("/health")).await;
+        assert_eq!(next_line().await, "Health");
+        assert_eq!(next_line().await, format!("main: Healthy (in maintenance until {until})"));
+        handler.handle(console_message("/health main")).await;
+        assert_eq!(next_line().await, "Server: main");
+        assert_eq!(next_line().await, format!("Health: Healthy (in maintenance until {until})"));
+
+        // Windows running into another day show the date
+        handler.maintenance_use_case.add_maintenance(MaintenanceConfig {
+            name: String::from("migration"),
+            server_name: String::from("main"),
+            schedule: Schedule { days: Vec::new(), time: start.time() },
+            duration: 2 * 24 * 60 * 60
+        }).await.unwrap();
+        let until = (start + chrono::TimeDelta::days(2)).format("%Y-%m-%d %H:%M");
+        handler.handle(console_message("/health main")).await;
+        assert_eq!(next_line().await, "Server: main");
+        assert_eq!(next_line().await, format!("Health: Healthy (in maintenance until {until})"));
+    }
+
+    #[tokio::test]
+    async fn ack_alert() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/register secret")).await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+        handler.auth_use_case.set_servers(chat_id.as_str(), Some(vec![String::from("db")])).await.unwrap();
+        let alert = handler.alert_use_case
+            .open_alert(String::from("main-error"), String::from("main"), String::from("ERROR in main")).await.unwrap();
+
+        handler.handle(console_message("/ack nope")).await;
+        assert_eq!(next_line().await, "[ERROR] Alert 'nope' not found");
+        handler.handle(console_message(format!("/ack {}", alert.id).as_str())).await;
+        assert_eq!(next_line().await, format!("[ERROR] Alert '{}' not found", alert.id));
+
+        // Escalation targets may ack outside their server scope
+        let mut event = handler.event_config_use_case.list_event().await.unwrap().remove(0);
+        event.escalation = vec![EscalationLevel { delay: 600, chat_ids: vec![chat_id.clone()] }];
+        handler.event_config_use_case.remove_event(event.name.clone()).await.unwrap();
+        handler.event_config_use_case.add_event(event).await.unwrap();
+
+        handler.handle(console_message(format!("/ack {}", alert.id).as_str())).await;
+        assert_eq!(next_line().await, format!("Alert {} of 'main-error' acknowledged.", alert.id));
+        handler.handle(console_message(format!("/ack {}", alert.id).as_str())).await;
+        assert_eq!(next_line().await, format!("[ERROR] Alert '{}' is already acknowledged by console:stdin (viewer, servers: db)", alert.id));
+    }
+
+    #[tokio::test]
+    async fn register_with_totp() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        let secret = totp::generate_secret();
+        let mut config = Config::new(Some(String::from("secret")));
+        config.totp_secret = Some(secret.clone());
+        let mut auth_adapter = AuthAdapter::new(Arc::new(MemoryFileAccessor::new(config)), Arc::new(MemoryFileAccessor::new(ChatList::new())), Arc::new(MemoryFileAccessor::new(TotpState::new())));
+        auth_adapter.init().await;
+        handler.auth_use_case = Box::new(auth_adapter);
+
+        handler.handle(console_message("/health")).await;
+        assert_eq!(next_line().await, "Registration required. Usage: /register <password> <code>");
+        handler.handle(console_message("/register secret")).await;
+        assert_eq!(next_line().await, "Invalid password or code. Usage: /register <password> <code>");
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let code = totp::code(secret.as_str(), now).unwrap();
+        handler.handle(console_message(format!("/register wrong {code}").as_str())).await;
+        assert_eq!(next_line().await, "Invalid password or code. Usage: /register <password> <code>");
+        handler.handle(console_message(format!("/register secret {code}").as_str())).await;
+        assert!(next_line().await.starts_with("Successfully registered. Chat id: "));
+    }
+
+    #[tokio::test]
+    async fn audit_records_commands() {
+        let (mut handler, _rx, mut lines) = setup(b"").await;
+        let mut next_line = async || lines.next_line().await.unwrap().unwrap();
+
+        handler.handle(console_message("/health")).await;
+        handler.handle(console_message("/register secret")).await;
+        next_line().await;
+        let chat_id = next_line().await.rsplit(' ').next().unwrap().to_string();
+        handler.handle(console_message("/restart main")).await;
+        handler.auth_use_case.set_role(chat_id.as_str(), Role::Operator).await.unwrap();
+        handler.handle(console_message("/restart main")).await;
+        next_line().await;
+        next_line().await;
+        let code = next_line().await.split_whitespace().nth(2).unwrap().to_string();
+        handler.handle(console_message(format!("/confirm {code}").as_str())).await;
+
+        let entries = handler.audit_logger.entries().await.unwrap();
+        let summary: Vec<_> = entries.iter()
+            .map(|entry| (entry.command.as_str(), entry.outcome, entry.registered_chat_id.is_some()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("HealthCheckAll", AuditOutcome::Unauthenticated, false),
+            ("Register", AuditOutcome::Success, true),
+            (r#"Restart("main")"#, AuditOutcome::Denied, true),
+            (r#"Restart("main")"#, AuditOutcome::Pending, true),
+            (r#"Confirm(Restart("main"))"#, AuditOutcome::Success, true)
+        ]);
+        assert_eq!(entries[1].registered_chat_id.as_deref(), Some(chat_id.as_str()));
+        assert_eq!(entries[4].registered_chat_id.as_deref(), Some(chat_id.as_str()));
+        assert_eq!(entries[4].detail.as_deref(), Some("'main' restarted"));
+        assert!(entries.iter().all(|entry| !entry.to_string().contains("secret")));
+    }
+}
@@ -3,4 +3,9 @@ pub mod server;
 pub mod client;
 pub mod chat;
 pub mod event;
-pub mod file_accessor;
\ No newline at end of file
+pub mod file_accessor;
+pub mod lockout;
+pub mod audit;
+pub mod silence;
+pub mod alert;
+pub mod totp;
\ No newline at end of file
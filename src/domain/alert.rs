@@ -0,0 +1,125 @@
+use std::ops::Range;
+use chrono::{DateTime, TimeDelta, Utc};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+use crate::domain::config::EscalationLevel;
+
+/// Alerts are dropped a day after the alarm, so escalation delays should stay below that.
+pub const ALERT_RETENTION: TimeDelta = TimeDelta::days(1);
+
+/// Delivered alarms, kept so they can be acknowledged and escalated.
+#[derive(Serialize, Deserialize, new, Clone, Debug)]
+pub struct AlertList {
+    #[new(default)]
+    #[serde(default)]
+    pub alerts: Vec<Alert>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Alert {
+    /// Short id used by `/ack`
+    pub id: String,
+    pub event_name: String,
+    pub server_name: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    /// Number of escalation levels sent so far
+    #[serde(default)]
+    pub escalated: usize,
+    /// Chat which sent `/ack`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged_by: Option<String>
+}
+
+impl Alert {
+    pub fn ack_command(&self) -> String {
+        format!("/ack {}", self.id)
+    }
+
+    /// Indexes of the levels in `policy` which are due at `now` and not sent yet.
+    /// Several levels are due at once if the watchdog was not running in between.
+    pub fn due_levels(&self, policy: &[EscalationLevel], now: DateTime<Utc>) -> Range<usize> {
+        if self.acknowledged_by.is_some() {
+            return self.escalated..self.escalated;
+        }
+        let due = policy.iter()
+            .skip(self.escalated)
+            .take_while(|level| {
+                i64::try_from(level.delay).ok()
+                    .and_then(TimeDelta::try_seconds)
+                    .and_then(|delay| self.created_at.checked_add_signed(delay))
+                    .is_some_and(|due| due <= now)
+            })
+            .count();
+        self.escalated..self.escalated + due
+    }
+}
+
+impl AlertList {
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Alert> {
+        self.alerts.iter_mut().find(|alert| alert.id == id)
+    }
+
+    /// Drops alerts created before `before`, returning whether any was dropped.
+    pub fn prune(&mut self, before: DateTime<Utc>) -> bool {
+        let len = self.alerts.len();
+        self.alerts.retain(|alert| alert.created_at >= before);
+        self.alerts.len() != len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(delay: u64, chat_id: &str) -> EscalationLevel {
+        EscalationLevel { delay, chat_ids: vec![chat_id.to_string()] }
+    }
+
+    #[test]
+    fn due_levels() {
+        let created_at = Utc::now();
+        let mut alert = Alert {
+            id: String::from("a1"),
+            event_name: String::from("main-down"),
+            server_name: String::from("main"),
+            text: String::from("main is down"),
+            created_at,
+            escalated: 0,
+            acknowledged_by: None
+        };
+        let policy = [level(600, "chat-2"), level(1800, "chat-3"), level(u64::MAX, "chat-4")];
+
+        assert_eq!(alert.due_levels(&policy, created_at + TimeDelta::minutes(5)), 0..0);
+        assert_eq!(alert.due_levels(&policy, created_at + TimeDelta::minutes(10)), 0..1);
+        assert_eq!(alert.due_levels(&policy, created_at + TimeDelta::hours(1)), 0..2);
+
+        alert.escalated = 1;
+        assert_eq!(alert.due_levels(&policy, created_at + TimeDelta::minutes(20)), 1..1);
+        assert_eq!(alert.due_levels(&policy, created_at + TimeDelta::hours(1)), 1..2);
+
+        alert.acknowledged_by = Some(String::from("telegram:1"));
+        assert!(alert.due_levels(&policy, created_at + TimeDelta::hours(1)).is_empty());
+    }
+
+    #[test]
+    fn prune() {
+        let now = Utc::now();
+        let mut list = AlertList::new();
+        for (id, age) in [("old", 25), ("new", 1)] {
+            list.alerts.push(Alert {
+                id: id.to_string(),
+                event_name: String::from("main-down"),
+                server_name: String::from("main"),
+                text: String::from("main is down"),
+                created_at: now - TimeDelta::hours(age),
+                escalated: 0,
+                acknowledged_by: None
+            });
+        }
+        assert!(list.prune(now - ALERT_RETENTION));
+        assert!(!list.prune(now - ALERT_RETENTION));
+        assert!(list.find_mut("old").is_none());
+        assert!(list.find_mut("new").is_some());
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One handled chat message. Holds the parsed command only, so passwords and codes never reach the log.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub client_name: String,
+    pub chat_id: String,
+    /// Id of the registered chat, None when the sender is not registered
+    pub registered_chat_id: Option<String>,
+    pub command: String,
+    pub outcome: AuditOutcome,
+    /// First line of the reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+    /// Refused by role, server scope or a lockout
+    Denied,
+    /// Waiting for `/confirm`
+    Pending,
+    Unauthenticated
+}
+
+impl AuditEntry {
+    pub fn new(client_name: String, chat_id: String, registered_chat_id: Option<String>, command: String, outcome: AuditOutcome, detail: Option<String>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            client_name,
+            chat_id,
+            registered_chat_id,
+            command,
+            outcome,
+            detail
+        }
+    }
+
+    /// Case-insensitive match against the printed entry.
+    pub fn matches(&self, query: &str) -> bool {
+        self.to_string().to_lowercase().contains(query.to_lowercase().as_str())
+    }
+}
+
+impl Display for AuditOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+            AuditOutcome::Denied => "denied",
+            AuditOutcome::Pending => "pending",
+            AuditOutcome::Unauthenticated => "unauthenticated"
+        };
+        write!(f, "{}", val)
+    }
+}
+
+impl Display for AuditEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "{} {}:{} [{}] {} {}",
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.client_name,
+            self.chat_id,
+            self.registered_chat_id.as_deref().unwrap_or("-"),
+            self.command,
+            self.outcome
+        )?;
+        if let Some(detail) = &self.detail {
+            write!(f, " ({detail})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_and_match() {
+        let mut entry = AuditEntry::new(
+            String::from("telegram"), String::from("42"), Some(String::from("c1")),
+            String::from(r#"Kill("main")"#), AuditOutcome::Success, Some(String::from("Kill signal sent to 'main'"))
+        );
+        entry.timestamp = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z").unwrap().to_utc();
+
+        assert_eq!(entry.to_string(), r#"2026-01-02T03:04:05Z telegram:42 [c1] Kill("main") success (Kill signal sent to 'main')"#);
+        assert!(entry.matches("kill(\"MAIN\")"));
+        assert!(!entry.matches("restart"));
+
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains(r#""outcome":"success""#));
+        assert_eq!(serde_json::from_str::<AuditEntry>(json.as_str()).unwrap().to_string(), entry.to_string());
+    }
+}
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use derive_new::new;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -9,11 +11,36 @@ pub struct ChatList {
     pub chats: Vec<Chat>
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+impl ChatList {
+    pub fn remove(&mut self, chat_id: &str) -> Option<Chat> {
+        let index = self.chats.iter().position(|chat| chat.id == chat_id)?;
+        Some(self.chats.remove(index))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Chat {
     pub id: String,
     pub client_name: String,
-    pub identity: String
+    pub identity: String,
+    /// Chats registered before roles existed keep full access
+    #[serde(default)]
+    pub role: Role,
+    /// Servers the chat may touch. None means every server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<String>>
+}
+
+/// Ordered by privilege, so a role includes every lower one.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Health, logs, events and alarms
+    Viewer,
+    /// Viewer plus kill and restart
+    Operator,
+    #[default]
+    Admin
 }
 
 impl Chat {
@@ -21,7 +48,53 @@ impl Chat {
         Self {
             id: Uuid::new_v4().to_string(),
             client_name,
-            identity
+            identity,
+            role: Role::Viewer,
+            servers: None
+        }
+    }
+
+    pub fn has_role(&self, role: Role) -> bool {
+        self.role >= role
+    }
+
+    /// One line summary, e.g. `telegram:42 (operator, servers: main)`.
+    pub fn describe(&self) -> String {
+        let servers = match &self.servers {
+            Some(servers) => servers.join(", "),
+            None => String::from("all")
+        };
+        format!("{}:{} ({}, servers: {})", self.client_name, self.identity, self.role, servers)
+    }
+
+    pub fn can_access(&self, server_name: &str) -> bool {
+        match &self.servers {
+            Some(servers) => servers.iter().any(|server| server == server_name),
+            None => true
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin"
+        };
+        write!(f, "{}", val)
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(Role::Viewer),
+            "operator" => Ok(Role::Operator),
+            "admin" => Ok(Role::Admin),
+            _ => Err(format!("unknown role '{s}' (viewer, operator, admin)"))
         }
     }
 }
@@ -32,15 +105,18 @@ pub struct ChatMap {
 
 impl ChatMap {
 
-    pub fn get_id(&self, client_name: &str, identity: &str) -> Option<&str> {
-        let chat = self.chats.get(&(client_name.to_string(), identity.to_string()))?;
-        Some(chat.id.as_str())
+    pub fn get(&self, client_name: &str, identity: &str) -> Option<&Chat> {
+        self.chats.get(&(client_name.to_string(), identity.to_string()))
     }
 
     pub fn contains(&self, client_name: &str, identity: &str) -> bool {
         self.chats.contains_key(&(client_name.to_string(), identity.to_string()))
     }
 
+    pub fn remove(&mut self, client_name: &str, identity: &str) -> Option<Chat> {
+        self.chats.remove(&(client_name.to_string(), identity.to_string()))
+    }
+
     pub fn from(chat_list: ChatList) -> Self {
         let mut chats = HashMap::new();
         for chat in chat_list.chats.into_iter() {
@@ -51,4 +127,43 @@ impl ChatMap {
             chats
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_chat_keeps_full_access() {
+        let chat: Chat = serde_json::from_str(r#"{"id": "1", "client_name": "telegram", "identity": "4"}"#).unwrap();
+
+        assert_eq!(chat.role, Role::Admin);
+        assert!(chat.has_role(Role::Operator));
+        assert!(chat.can_access("main"));
+    }
+
+    #[test]
+    fn new_chat_is_viewer() {
+        let mut chat = Chat::new(String::from("telegram"), String::from("4"));
+        chat.servers = Some(vec![String::from("main")]);
+
+        assert!(chat.has_role(Role::Viewer));
+        assert!(!chat.has_role(Role::Operator));
+        assert!(chat.can_access("main"));
+        assert!(!chat.can_access("other"));
+    }
+
+    #[test]
+    fn remove_chat() {
+        let chat = Chat::new(String::from("telegram"), String::from("4"));
+        let mut chat_list = ChatList::new();
+        chat_list.chats.push(chat.clone());
+        let mut chat_map = ChatMap::from(chat_list.clone());
+
+        assert!(chat_list.remove("unknown").is_none());
+        assert_eq!(chat_list.remove(chat.id.as_str()).unwrap().id, chat.id);
+        assert!(chat_list.chats.is_empty());
+
+        assert!(chat_map.remove("telegram", "4").is_some());
+        assert!(!chat_map.contains("telegram", "4"));
+    }
+}
@@ -5,4 +5,108 @@ pub struct Message {
     pub client_name: String,
     pub chat_id: String,
     pub data: String
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error
+}
+
+/// Structured response to a chat, rendered natively by each client.
+/// Buttons send their `data` back as a message when pressed, on clients which support them.
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    pub severity: Severity,
+    pub title: Option<String>,
+    pub text: String,
+    pub fields: Vec<(String, String)>,
+    pub code: Option<String>,
+    pub buttons: Vec<Vec<Button>>
+}
+
+#[derive(new, Debug, Clone)]
+pub struct Button {
+    pub text: String,
+    pub data: String
+}
+
+impl Reply {
+    pub fn titled(title: impl Into<String>) -> Self {
+        Self {
+            title: Some(title.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    pub fn with_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn with_buttons(mut self, buttons: Vec<Vec<Button>>) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.text.is_empty() && self.fields.is_empty() && self.code.is_none()
+    }
+
+    /// Default rendering for clients without formatting.
+    pub fn to_plain_text(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(title) = &self.title {
+            lines.push(title.clone());
+        }
+        if !self.text.is_empty() {
+            lines.push(self.text.clone());
+        }
+        for (name, value) in &self.fields {
+            lines.push(format!("{name}: {value}"));
+        }
+        if let Some(code) = &self.code {
+            lines.push(code.clone());
+        }
+
+        let text = lines.join("\n");
+        match self.severity {
+            Severity::Warning => format!("[WARNING] {text}"),
+            Severity::Error => format!("[ERROR] {text}"),
+            Severity::Info | Severity::Success => text
+        }
+    }
+}
+
+impl From<String> for Reply {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for Reply {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
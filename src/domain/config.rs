@@ -1,12 +1,14 @@
 mod event;
 mod client;
 mod server;
+mod maintenance;
 
 use derive_new::new;
 use serde::{Deserialize, Serialize};
 pub use event::*;
 pub use client::*;
 pub use server::*;
+pub use maintenance::*;
 
 #[derive(Serialize, Deserialize, Debug, new, Clone)]
 pub struct Config {
@@ -16,5 +18,29 @@ pub struct Config {
     #[new(default)]
     pub servers: Vec<ServerConfig>,
     #[new(default)]
-    pub events: Vec<EventConfig>
+    pub events: Vec<EventConfig>,
+    /// Replies with more code (e.g. logs) than this many bytes are sent as a file, where supported
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment_threshold: Option<usize>,
+    /// Base32 TOTP secret. When set, `/register` also needs a code from an authenticator app
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp_secret: Option<String>,
+    /// Buffers alarms per chat and sends them together, instead of one message per alarm
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<DigestConfig>,
+    /// Recurring windows in which alarms of a server are suppressed
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintenance_windows: Vec<MaintenanceConfig>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DigestConfig {
+    /// Seconds after the first buffered alarm until the digest is sent
+    pub interval: u64,
+    /// Sends the digest early once this many alarms are buffered
+    pub max_items: usize
 }
\ No newline at end of file
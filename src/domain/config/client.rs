@@ -1,10 +1,93 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct ClientConfig {
     pub name: String,
-    pub kind: String, // ex: telegram
-    pub token: Option<String>
+    pub kind: String, // ex: telegram, slack, discord, matrix, webhook, email, console
+    pub token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_token: Option<String>, // slack: app-level token for socket mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>, // overrides the default api url, matrix: homeserver url
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_channels: Option<Vec<String>>, // discord: channels the bot listens to, all if None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>, // webhook: endpoint to post to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>, // webhook: extra request headers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_template: Option<String>, // webhook: json body, {{message}} and {{chat_id}} are replaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp: Option<SmtpConfig>, // email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<String>, // console: read from a unix socket instead of stdin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookReceiverConfig> // telegram: receive updates by webhook instead of long polling
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WebhookReceiverConfig {
+    pub url: String, // public url registered with setWebhook
+    pub listen_addr: String, // ex: 0.0.0.0:8080
+    pub secret_token: Option<String> // generated on each run if None
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<SmtpSecurity>,
+    /// Read from configs written before `security`
+    #[serde(default, skip_serializing)]
+    pub starttls: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmtpSecurity {
+    /// Implicit TLS, usually on port 465
+    Tls,
+    /// Upgrades the connection, usually on port 587
+    Starttls,
+    /// Only allowed without credentials, e.g. for a local relay
+    Plaintext
+}
+
+impl SmtpConfig {
+    pub fn security(&self) -> SmtpSecurity {
+        self.security.unwrap_or(if self.starttls { SmtpSecurity::Starttls } else { SmtpSecurity::Plaintext })
+    }
+}
+
+impl Display for SmtpSecurity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            SmtpSecurity::Tls => "tls",
+            SmtpSecurity::Starttls => "starttls",
+            SmtpSecurity::Plaintext => "plaintext"
+        };
+        write!(f, "{}", val)
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tls" => Ok(SmtpSecurity::Tls),
+            "starttls" => Ok(SmtpSecurity::Starttls),
+            "plaintext" => Ok(SmtpSecurity::Plaintext),
+            _ => Err(format!("unknown security '{s}' (tls, starttls, plaintext)"))
+        }
+    }
 }
 
 impl ClientConfig {
@@ -12,7 +95,74 @@ impl ClientConfig {
         Self {
             name: String::from(name),
             kind: String::from("telegram"),
-            token: Some(String::from(token))
+            token: Some(String::from(token)),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_telegram_webhook(name: &str, token: &str, webhook: WebhookReceiverConfig) -> Self {
+        Self {
+            webhook: Some(webhook),
+            ..Self::new_telegram(name, token)
         }
     }
-}
\ No newline at end of file
+
+    pub fn new_slack(name: &str, token: &str, app_token: &str) -> Self {
+        Self {
+            name: String::from(name),
+            kind: String::from("slack"),
+            token: Some(String::from(token)),
+            app_token: Some(String::from(app_token)),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_discord(name: &str, token: &str, allowed_channels: Option<Vec<String>>) -> Self {
+        Self {
+            name: String::from(name),
+            kind: String::from("discord"),
+            token: Some(String::from(token)),
+            allowed_channels,
+            ..Default::default()
+        }
+    }
+
+    pub fn new_matrix(name: &str, homeserver_url: &str, access_token: &str) -> Self {
+        Self {
+            name: String::from(name),
+            kind: String::from("matrix"),
+            token: Some(String::from(access_token)),
+            base_url: Some(String::from(homeserver_url)),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_webhook(name: &str, url: &str, headers: Option<HashMap<String, String>>, body_template: Option<String>) -> Self {
+        Self {
+            name: String::from(name),
+            kind: String::from("webhook"),
+            url: Some(String::from(url)),
+            headers,
+            body_template,
+            ..Default::default()
+        }
+    }
+
+    pub fn new_email(name: &str, smtp: SmtpConfig) -> Self {
+        Self {
+            name: String::from(name),
+            kind: String::from("email"),
+            smtp: Some(smtp),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_console(name: &str, socket_path: Option<String>) -> Self {
+        Self {
+            name: String::from(name),
+            kind: String::from("console"),
+            socket_path,
+            ..Default::default()
+        }
+    }
+}
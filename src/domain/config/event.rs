@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use derive_new::new;
 use serde::{Deserialize, Serialize};
 
@@ -6,7 +8,65 @@ pub struct EventConfig {
     pub r#type: String, // logs, health
     pub name: String,
     pub target: String, // target server
-    pub keyword: String,
+    pub keyword: String, // a regex pattern in regex mode
+    #[serde(default)]
+    pub match_mode: MatchMode,
+    /// Alarms when the keyword does not match
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub negate: bool,
+    /// Alarm text template, e.g. `{{server}} failed with {{code}}`. None keeps the default text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Seconds after an alarm in which further alarms of the event are collapsed into one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooldown: Option<u64>,
+    /// Seconds after an alarm in which alarms with the same text are collapsed into one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window: Option<u64>,
+    /// Chats the alarm is sent to next, as long as nobody sends `/ack`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub escalation: Vec<EscalationLevel>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EscalationLevel {
+    /// Seconds after the alarm
+    pub delay: u64,
+    pub chat_ids: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatchMode {
+    #[default]
+    Substring,
+    CaseInsensitive,
+    /// Capture groups can be used in the message template
+    Regex
+}
+
+impl Display for MatchMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            MatchMode::Substring => "substring",
+            MatchMode::CaseInsensitive => "case-insensitive",
+            MatchMode::Regex => "regex"
+        };
+        write!(f, "{}", val)
+    }
+}
+
+impl FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "substring" => Ok(MatchMode::Substring),
+            "case-insensitive" => Ok(MatchMode::CaseInsensitive),
+            "regex" => Ok(MatchMode::Regex),
+            _ => Err(format!("unknown match mode '{s}' (substring, case-insensitive, regex)"))
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, new, Clone)]
@@ -23,6 +83,13 @@ impl EventSubscribeList {
         }
     }
 
+    /// Drops a chat from every event, e.g. when it is revoked.
+    pub fn remove_chat(&mut self, chat_id: &str) {
+        for subscribe in self.subscribes.iter_mut() {
+            subscribe.chat_ids.retain(|id| id != chat_id);
+        }
+    }
+
     pub fn find_subscribed_events(&self, chat_id: &str) -> Vec<&str> {
         self.subscribes.iter()
             .filter(|subscribe| {subscribe.contains(chat_id)})
@@ -0,0 +1,139 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use chrono::{DateTime, Datelike, Days, NaiveTime, TimeDelta, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Longest window. As schedules repeat weekly, a longer window would never end anyway.
+pub const MAX_MAINTENANCE_DURATION: u64 = 7 * 24 * 60 * 60;
+
+/// A recurring window in which alarms of a server are suppressed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MaintenanceConfig {
+    pub name: String,
+    pub server_name: String,
+    pub schedule: Schedule,
+    /// Seconds from each start of the schedule
+    pub duration: u64
+}
+
+/// Weekly start times in local time, written as e.g. `daily 03:00` or `sat,sun 02:30`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Schedule {
+    /// Empty for every day
+    pub days: Vec<Weekday>,
+    pub time: NaiveTime
+}
+
+impl MaintenanceConfig {
+    /// End of the window covering `now`, if any. Windows may run past midnight and overlap.
+    pub fn active_until<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        // Hand-edited configs may exceed the limit, which covers the same times
+        let seconds = self.duration.min(MAX_MAINTENANCE_DURATION);
+        let duration = TimeDelta::try_seconds(seconds as i64)?;
+        let today = now.date_naive();
+        (0..=seconds / (24 * 60 * 60) + 1)
+            .filter_map(|days| today.checked_sub_days(Days::new(days)))
+            .filter(|date| self.schedule.runs_on(date.weekday()))
+            .filter_map(|date| date.and_time(self.schedule.time).and_local_timezone(now.timezone()).earliest())
+            .filter(|start| start <= now)
+            .filter_map(|start| start.checked_add_signed(duration))
+            .filter(|end| now < end)
+            .max()
+    }
+}
+
+impl Schedule {
+    pub fn runs_on(&self, weekday: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&weekday)
+    }
+}
+
+impl Display for Schedule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let days = if self.days.is_empty() {
+            String::from("daily")
+        } else {
+            self.days.iter()
+                .map(|day| day.to_string().to_lowercase())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(f, "{} {}", days, self.time.format("%H:%M"))
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid schedule '{s}' (e.g. daily 03:00, sat,sun 02:30)");
+        let (days, time) = s.trim().split_once(' ').ok_or_else(invalid)?;
+        let time = NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| invalid())?;
+        let days = match days {
+            "daily" => Vec::new(),
+            _ => days.split(',')
+                .map(|day| day.parse::<Weekday>().map_err(|_| invalid()))
+                .collect::<Result<_, _>>()?
+        };
+        Ok(Schedule { days, time })
+    }
+}
+
+impl TryFrom<String> for Schedule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Schedule> for String {
+    fn from(value: Schedule) -> Self {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use super::*;
+
+    fn window(schedule: &str, duration: u64) -> MaintenanceConfig {
+        MaintenanceConfig {
+            name: String::from("backup"),
+            server_name: String::from("main"),
+            schedule: schedule.parse().unwrap(),
+            duration
+        }
+    }
+
+    #[test]
+    fn parse_schedule() {
+        let schedule: Schedule = "sat,sun 02:30".parse().unwrap();
+        assert_eq!(schedule.days, vec![Weekday::Sat, Weekday::Sun]);
+        assert_eq!(schedule.to_string(), "sat,sun 02:30");
+        assert_eq!("daily 03:00".parse::<Schedule>().unwrap().to_string(), "daily 03:00");
+        assert!("daily".parse::<Schedule>().is_err());
+        assert!("someday 03:00".parse::<Schedule>().is_err());
+        assert!("daily 25:00".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn active_window() {
+        // 2026-10-17 is a Saturday
+        let at = |text: &str| text.parse::<DateTime<Utc>>().unwrap();
+        let nightly = window("sat 23:00", 2 * 60 * 60);
+
+        assert_eq!(nightly.active_until(&at("2026-10-17T22:59:59Z")), None);
+        assert_eq!(nightly.active_until(&at("2026-10-17T23:00:00Z")), Some(at("2026-10-18T01:00:00Z")));
+        // Runs past midnight into Sunday
+        assert_eq!(nightly.active_until(&at("2026-10-18T00:30:00Z")), Some(at("2026-10-18T01:00:00Z")));
+        assert_eq!(nightly.active_until(&at("2026-10-18T01:00:00Z")), None);
+        assert_eq!(nightly.active_until(&at("2026-10-18T23:30:00Z")), None);
+
+        // Capped at a week, from the last start
+        let endless = window("sat 23:00", u64::MAX);
+        assert_eq!(endless.active_until(&at("2026-10-18T00:30:00Z")), Some(at("2026-10-24T23:00:00Z")));
+    }
+}
@@ -8,17 +8,32 @@ pub struct ServerConfig {
     pub health_check_path: Option<String>,
     pub kill_path: Option<String>,
     pub log_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<RestartMethod>,
+}
+
+/// How `/restart` restarts a server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RestartMethod {
+    /// `docker restart` of the server's container
+    Docker,
+    /// `systemctl restart <unit>`
+    Systemd { unit: String },
+    /// Any command, split on whitespace like the log command
+    Command { command: String }
 }
 
 impl ServerConfig {
-    pub fn new(name: String, base_url: Option<String>, docker_container_name: Option<String>, health_check_path: Option<String>, kill_path: Option<String>, log_command: Option<String>,) -> Self {
+    pub fn new(name: String, base_url: Option<String>, docker_container_name: Option<String>, health_check_path: Option<String>, kill_path: Option<String>, log_command: Option<String>, restart: Option<RestartMethod>) -> Self {
         Self {
             name: String::from(name),
             base_url,
             docker_container_name,
             health_check_path,
             kill_path,
-            log_command
+            log_command,
+            restart
         }
     }
 }
@@ -1,9 +1,27 @@
+mod matcher;
+mod transition;
+mod throttle;
+mod digest;
+mod duration;
+
+use std::error::Error;
+use std::time::Duration;
 use crate::domain::config::EventConfig;
+pub use matcher::*;
+pub use transition::*;
+pub use throttle::*;
+pub use digest::*;
+pub use duration::*;
 
 #[derive(Clone)]
 pub struct Event {
     pub name: String,
-    pub event_kind: EventKind
+    pub event_kind: EventKind,
+    pub matcher: Matcher,
+    /// Alarm text template, see [render]
+    pub message: Option<String>,
+    pub cooldown: Option<Duration>,
+    pub dedup_window: Option<Duration>
 }
 
 #[derive(Clone)]
@@ -16,12 +34,22 @@ pub enum EventKind {
         server_name: String,
         keyword: String
     },
+    /// Reports changes of health, including recovery, instead of every match
+    HealthTransition {
+        server_name: String
+    },
     None
 }
 
-impl Event {
-    
-    pub fn from(event_config: EventConfig) -> Self {
+impl TryFrom<EventConfig> for Event {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn try_from(event_config: EventConfig) -> Result<Self, Self::Error> {
+        // A log stream has no end to check against, so a negated matcher would fire on every other line
+        if event_config.negate && event_config.r#type != "health" {
+            return Err("negate is only supported by health events".into());
+        }
+        let matcher = Matcher::new(event_config.match_mode, event_config.keyword.as_str(), event_config.negate)?;
         let event_kind = match event_config.r#type.as_str() {
             "logs" => {
                 EventKind::Log {
@@ -35,11 +63,40 @@ impl Event {
                     keyword: event_config.keyword
                 }
             },
+            "health-transition" => {
+                EventKind::HealthTransition {
+                    server_name: event_config.target
+                }
+            },
             _ => EventKind::None
         };
-        Self {
+        Ok(Self {
             name: event_config.name,
-            event_kind
+            event_kind,
+            matcher,
+            message: event_config.message,
+            cooldown: event_config.cooldown.map(Duration::from_secs),
+            dedup_window: event_config.dedup_window.map(Duration::from_secs)
+        })
+    }
+}
+
+impl Event {
+    pub fn throttle(&self) -> Throttle {
+        Throttle::new(self.cooldown, self.dedup_window)
+    }
+
+    /// Alarm text for a match: the template filled with the captures, or `default`.
+    /// Besides the captures, templates can use `{{event}}`, `{{server}}` and `{{line}}`.
+    pub fn alarm_text(&self, server_name: &str, line: &str, mut captures: Captures, default: impl FnOnce() -> String) -> String {
+        match &self.message {
+            Some(template) => {
+                captures.insert(String::from("event"), self.name.clone());
+                captures.insert(String::from("server"), server_name.to_string());
+                captures.insert(String::from("line"), line.to_string());
+                render(template.as_str(), &captures)
+            },
+            None => default()
         }
     }
-}
\ No newline at end of file
+}
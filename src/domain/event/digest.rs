@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Buffers items per key (e.g. per chat) until the first item is `interval` old or `max_items` are queued.
+pub struct DigestBuffer<K, T> {
+    interval: Duration,
+    max_items: usize,
+    buffers: HashMap<K, Buffer<T>>
+}
+
+struct Buffer<T> {
+    started_at: Instant,
+    items: Vec<T>
+}
+
+impl<K: Hash + Eq + Clone, T> DigestBuffer<K, T> {
+    pub fn new(interval: Duration, max_items: usize) -> Self {
+        Self {
+            interval,
+            max_items: max_items.max(1),
+            buffers: HashMap::new()
+        }
+    }
+
+    /// Queues an item and returns the buffer of the key once it is full.
+    pub fn push(&mut self, key: K, item: T, now: Instant) -> Option<Vec<T>> {
+        let buffer = self.buffers.entry(key.clone())
+            .or_insert_with(|| Buffer { started_at: now, items: Vec::new() });
+        buffer.items.push(item);
+
+        if buffer.items.len() >= self.max_items {
+            return self.buffers.remove(&key).map(|buffer| buffer.items);
+        }
+        None
+    }
+
+    /// Buffers which are due at `now`.
+    pub fn flush(&mut self, now: Instant) -> Vec<(K, Vec<T>)> {
+        let due: Vec<K> = self.buffers.iter()
+            .filter(|(_, buffer)| buffer.started_at + self.interval <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        due.into_iter()
+            .filter_map(|key| self.buffers.remove(&key).map(|buffer| (key, buffer.items)))
+            .collect()
+    }
+
+    /// Every buffer, e.g. on shutdown.
+    pub fn drain(&mut self) -> Vec<(K, Vec<T>)> {
+        self.buffers.drain()
+            .map(|(key, buffer)| (key, buffer.items))
+            .collect()
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.buffers.values()
+            .map(|buffer| buffer.started_at + self.interval)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flush_by_count_and_time() {
+        let start = Instant::now();
+        let mut digest = DigestBuffer::new(Duration::from_secs(60), 3);
+
+        assert_eq!(digest.push("a", 1, start), None);
+        assert_eq!(digest.push("a", 2, start), None);
+        assert_eq!(digest.push("b", 10, start + Duration::from_secs(30)), None);
+        assert_eq!(digest.push("a", 3, start + Duration::from_secs(1)), Some(vec![1, 2, 3]));
+
+        assert_eq!(digest.next_deadline(), Some(start + Duration::from_secs(90)));
+        assert!(digest.flush(start + Duration::from_secs(89)).is_empty());
+        assert_eq!(digest.flush(start + Duration::from_secs(90)), vec![("b", vec![10])]);
+        assert_eq!(digest.next_deadline(), None);
+
+        assert_eq!(digest.push("c", 20, start), None);
+        assert_eq!(digest.drain(), vec![("c", vec![20])]);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use regex::{Regex, RegexBuilder};
+use crate::domain::config::MatchMode;
+
+/// Values of a match, by capture group name or index (`0` is the whole match).
+pub type Captures = HashMap<String, String>;
+
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    pattern: Pattern,
+    negate: bool
+}
+
+#[derive(Clone, Debug)]
+enum Pattern {
+    Substring(String),
+    /// Kept as a regex, so non-ASCII text folds case correctly
+    CaseInsensitive(Regex),
+    Regex(Regex)
+}
+
+impl Matcher {
+    pub fn new(mode: MatchMode, keyword: &str, negate: bool) -> Result<Self, regex::Error> {
+        let pattern = match mode {
+            MatchMode::Substring => Pattern::Substring(keyword.to_string()),
+            MatchMode::CaseInsensitive => Pattern::CaseInsensitive(
+                RegexBuilder::new(regex::escape(keyword).as_str()).case_insensitive(true).build()?
+            ),
+            MatchMode::Regex => Pattern::Regex(Regex::new(keyword)?)
+        };
+        Ok(Self { pattern, negate })
+    }
+
+    /// Captures of the first match, or empty captures for a negated matcher which did not match.
+    pub fn find(&self, text: &str) -> Option<Captures> {
+        let captures = match &self.pattern {
+            Pattern::Substring(keyword) => text.contains(keyword.as_str())
+                .then(|| HashMap::from([(String::from("0"), keyword.clone())])),
+            Pattern::CaseInsensitive(regex) => regex.find(text)
+                .map(|found| HashMap::from([(String::from("0"), found.as_str().to_string())])),
+            Pattern::Regex(regex) => regex.captures(text).map(|found| {
+                let mut captures = Captures::new();
+                for (index, name) in regex.capture_names().enumerate() {
+                    if let Some(value) = found.get(index) {
+                        captures.insert(index.to_string(), value.as_str().to_string());
+                        if let Some(name) = name {
+                            captures.insert(name.to_string(), value.as_str().to_string());
+                        }
+                    }
+                }
+                captures
+            })
+        };
+
+        match (captures, self.negate) {
+            (Some(captures), false) => Some(captures),
+            (None, true) => Some(Captures::new()),
+            _ => None
+        }
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negate
+    }
+}
+
+/// Replaces `{{name}}` placeholders in one pass, so values are never expanded again.
+/// Unknown placeholders are left as they are.
+pub fn render(template: &str, values: &Captures) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{(\w+)\}\}").unwrap())
+        .replace_all(template, |placeholder: &regex::Captures| {
+            values.get(&placeholder[1]).cloned().unwrap_or_else(|| placeholder[0].to_string())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_modes() {
+        let substring = Matcher::new(MatchMode::Substring, "ERROR", false).unwrap();
+        assert!(substring.find("an ERROR here").is_some());
+        assert!(substring.find("an error here").is_none());
+
+        let case_insensitive = Matcher::new(MatchMode::CaseInsensitive, "error.", false).unwrap();
+        assert_eq!(case_insensitive.find("an Error. here").unwrap()["0"], "Error.");
+        assert!(case_insensitive.find("an errors here").is_none());
+
+        let negated = Matcher::new(MatchMode::Substring, "Healthy", true).unwrap();
+        assert_eq!(negated.find("Down"), Some(Captures::new()));
+        assert!(negated.find("Healthy").is_none());
+
+        assert!(Matcher::new(MatchMode::Regex, "(", false).is_err());
+    }
+
+    #[test]
+    fn regex_captures_fill_template() {
+        let matcher = Matcher::new(MatchMode::Regex, r"status=(5\d\d) ERROR (?P<code>\w+)", false).unwrap();
+        assert!(matcher.find("status=404 ERROR E1").is_none());
+
+        let mut captures = matcher.find("GET / status=503 ERROR E_DB timeout").unwrap();
+        assert_eq!(captures["1"], "503");
+        assert_eq!(captures["code"], "E_DB");
+
+        captures.insert(String::from("server"), String::from("main {{code}}"));
+        assert_eq!(
+            render("{{server}}: {{1}} ({{code}}) {{unknown}}", &captures),
+            "main {{code}}: 503 (E_DB) {{unknown}}"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::domain::event::format_duration;
+
+/// Longest cooldown or dedup window. Hand-edited configs may exceed it, so longer ones are capped.
+pub const MAX_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Collapses repeated alarms of one event. The first alarm is sent right away, the ones
+/// following within the window are only counted and summed up in one message when it ends.
+pub struct Throttle {
+    /// Window for any alarm of the event
+    cooldown: Option<Duration>,
+    /// Window for alarms with the same text
+    dedup_window: Option<Duration>,
+    /// By text for dedup windows, None for the cooldown window
+    windows: HashMap<Option<String>, Window>
+}
+
+struct Window {
+    ends_at: Instant,
+    length: Duration,
+    suppressed: u32,
+    last_text: String
+}
+
+impl Throttle {
+    pub fn new(cooldown: Option<Duration>, dedup_window: Option<Duration>) -> Self {
+        Self {
+            cooldown,
+            dedup_window,
+            windows: HashMap::new()
+        }
+    }
+
+    /// Texts to send for a new alarm: summaries of ended windows, then the alarm unless it is collapsed.
+    pub fn alarm(&mut self, text: String, now: Instant) -> Vec<String> {
+        let mut texts = self.flush(now);
+
+        let key = [Some(text.clone()), None].into_iter()
+            .find(|key| self.windows.contains_key(key));
+        if let Some(key) = key {
+            let window = self.windows.get_mut(&key).unwrap();
+            window.suppressed += 1;
+            window.last_text = text;
+            return texts;
+        }
+
+        if let Some(length) = self.dedup_window {
+            self.windows.insert(Some(text.clone()), Window::new(now, length, text.clone()));
+        }
+        if let Some(length) = self.cooldown {
+            self.windows.insert(None, Window::new(now, length, text.clone()));
+        }
+        texts.push(text);
+        texts
+    }
+
+    /// Summaries of windows which ended with collapsed alarms. Such a window starts over,
+    /// so an ongoing storm is reported once per window.
+    pub fn flush(&mut self, now: Instant) -> Vec<String> {
+        let mut texts = Vec::new();
+        self.windows.retain(|_, window| {
+            if window.ends_at > now {
+                return true;
+            }
+            if window.suppressed == 0 {
+                return false;
+            }
+            texts.push(window.summary());
+            *window = Window::new(now, window.length, window.last_text.clone());
+            true
+        });
+        texts
+    }
+
+    /// Summaries of every window with collapsed alarms, e.g. when the source ends.
+    pub fn drain(&mut self) -> Vec<String> {
+        self.windows.drain()
+            .filter(|(_, window)| window.suppressed > 0)
+            .map(|(_, window)| window.summary())
+            .collect()
+    }
+
+    /// When the next window ends, if any is open.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.windows.values().map(|window| window.ends_at).min()
+    }
+}
+
+impl Window {
+    fn new(now: Instant, length: Duration, last_text: String) -> Self {
+        let length = length.min(MAX_WINDOW);
+        Self {
+            ends_at: now + length,
+            length,
+            suppressed: 0,
+            last_text
+        }
+    }
+
+    fn summary(&self) -> String {
+        let times = if self.suppressed == 1 { "time" } else { "times" };
+        format!(
+            "{}\n(occurred {} more {times} in the last {})",
+            self.last_text, self.suppressed, format_duration(self.length)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cooldown_collapses_any_alarm() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(Some(Duration::from_secs(60)), None);
+
+        assert_eq!(throttle.alarm(String::from("a"), start), vec!["a"]);
+        assert!(throttle.alarm(String::from("b"), start + Duration::from_secs(1)).is_empty());
+        assert!(throttle.alarm(String::from("c"), start + Duration::from_secs(2)).is_empty());
+        assert_eq!(throttle.next_deadline(), Some(start + Duration::from_secs(60)));
+
+        assert_eq!(throttle.flush(start + Duration::from_secs(60)), vec!["c\n(occurred 2 more times in the last 1m 0s)"]);
+        // The storm is over, so the reopened window ends quietly
+        assert!(throttle.flush(start + Duration::from_secs(120)).is_empty());
+        assert_eq!(throttle.next_deadline(), None);
+        assert_eq!(throttle.alarm(String::from("d"), start + Duration::from_secs(121)), vec!["d"]);
+    }
+
+    #[test]
+    fn dedup_collapses_identical_alarms() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(None, Some(Duration::from_secs(30)));
+
+        assert_eq!(throttle.alarm(String::from("a"), start), vec!["a"]);
+        assert_eq!(throttle.alarm(String::from("b"), start), vec!["b"]);
+        assert!(throttle.alarm(String::from("a"), start).is_empty());
+        assert!(throttle.alarm(String::from("a"), start).is_empty());
+
+        assert_eq!(
+            throttle.alarm(String::from("b"), start + Duration::from_secs(30)),
+            vec!["a\n(occurred 2 more times in the last 30s)", "b"]
+        );
+        assert!(throttle.alarm(String::from("b"), start + Duration::from_secs(31)).is_empty());
+        assert_eq!(throttle.drain(), vec!["b\n(occurred 1 more time in the last 30s)"]);
+    }
+
+    #[test]
+    fn disabled_throttle_sends_everything() {
+        let mut throttle = Throttle::new(None, None);
+
+        assert_eq!(throttle.alarm(String::from("a"), Instant::now()), vec!["a"]);
+        assert_eq!(throttle.alarm(String::from("a"), Instant::now()), vec!["a"]);
+        assert_eq!(throttle.next_deadline(), None);
+    }
+
+    #[test]
+    fn huge_windows_are_capped() {
+        let start = Instant::now();
+        let mut throttle = Throttle::new(Some(Duration::from_secs(u64::MAX)), Some(Duration::MAX));
+
+        assert_eq!(throttle.alarm(String::from("a"), start), vec!["a"]);
+        assert!(throttle.alarm(String::from("a"), start).is_empty());
+        assert_eq!(throttle.next_deadline(), Some(start + MAX_WINDOW));
+        assert_eq!(throttle.flush(start + MAX_WINDOW), vec!["a\n(occurred 1 more time in the last 24h 0m 0s)"]);
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+use crate::domain::event::format_duration;
+use crate::domain::server::health::Health;
+
+/// Remembers the last health of a server, so only changes are reported.
+#[derive(Default)]
+pub struct HealthTracker {
+    last: Option<Health>,
+    /// When the server left `Healthy`, kept across changes between unhealthy states
+    unhealthy_since: Option<Instant>
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Transition {
+    /// None for the first check
+    pub from: Option<Health>,
+    pub to: Health,
+    /// How long the server was not healthy, set when it recovers
+    pub outage: Option<Duration>
+}
+
+impl HealthTracker {
+    /// Records a health check and returns the change, if any.
+    /// A server which is healthy on the first check is not reported.
+    pub fn observe(&mut self, health: Health, now: Instant) -> Option<Transition> {
+        if self.last.as_ref() == Some(&health) {
+            return None;
+        }
+        let from = self.last.replace(health.clone());
+
+        let outage = if health == Health::Healthy {
+            self.unhealthy_since.take().map(|since| now.duration_since(since))
+        } else {
+            self.unhealthy_since.get_or_insert(now);
+            None
+        };
+
+        if from.is_none() && health == Health::Healthy {
+            return None;
+        }
+        Some(Transition { from, to: health, outage })
+    }
+}
+
+impl Transition {
+    pub fn text(&self, server_name: &str) -> String {
+        let was = self.from.as_ref()
+            .map(|from| format!(" (was {from})"))
+            .unwrap_or_default();
+        match self.outage {
+            Some(outage) if self.is_recovery() => format!("Server '{server_name}' recovered after {}{was}", format_duration(outage)),
+            _ => format!("Server '{server_name}' is {}{was}", self.to)
+        }
+    }
+
+    pub fn is_recovery(&self) -> bool {
+        self.to == Health::Healthy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_changes_only() {
+        let start = Instant::now();
+        let mut tracker = HealthTracker::default();
+
+        assert_eq!(tracker.observe(Health::Healthy, start), None);
+        assert_eq!(tracker.observe(Health::Healthy, start), None);
+
+        let down = tracker.observe(Health::Down, start + Duration::from_secs(10)).unwrap();
+        assert_eq!(down.text("main"), "Server 'main' is Down (was Healthy)");
+        assert_eq!(tracker.observe(Health::Down, start + Duration::from_secs(40)), None);
+
+        let degraded = tracker.observe(Health::Degraded, start + Duration::from_secs(70)).unwrap();
+        assert_eq!(degraded.text("main"), "Server 'main' is Degraded (was Down)");
+
+        let recovered = tracker.observe(Health::Healthy, start + Duration::from_secs(3735)).unwrap();
+        assert!(recovered.is_recovery());
+        assert_eq!(recovered.outage, Some(Duration::from_secs(3725)));
+        assert_eq!(recovered.text("main"), "Server 'main' recovered after 1h 2m 5s (was Degraded)");
+    }
+
+    #[test]
+    fn reports_unhealthy_first_check() {
+        let mut tracker = HealthTracker::default();
+
+        let first = tracker.observe(Health::Down, Instant::now()).unwrap();
+        assert_eq!(first.text("main"), "Server 'main' is Down");
+    }
+}
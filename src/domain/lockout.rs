@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+/// Failed `/register` attempts, per chat (`client_name:identity`) and over all chats.
+#[derive(Serialize, Deserialize, new, Clone, Debug)]
+pub struct LockoutState {
+    #[new(default)]
+    #[serde(default)]
+    pub chats: HashMap<String, Attempts>,
+    #[new(default)]
+    #[serde(default)]
+    pub global: Attempts
+}
+
+/// Times are unix seconds.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Attempts {
+    pub failures: u32,
+    pub last_failure: u64,
+    pub locked_until: u64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockoutScope {
+    Chat,
+    Global
+}
+
+/// A lockout started by a failed attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lockout {
+    pub scope: LockoutScope,
+    pub failures: u32,
+    pub seconds: u64
+}
+
+pub struct LockoutPolicy {
+    /// Failures before the first lockout
+    pub threshold: u32,
+    /// First lockout in seconds, doubled on every further failure
+    pub base: u64,
+    pub max: u64,
+    /// Failures are forgotten after this many quiet seconds
+    pub reset_after: u64
+}
+
+pub const CHAT_LOCKOUT_POLICY: LockoutPolicy = LockoutPolicy {
+    threshold: 3,
+    base: 30,
+    max: 60 * 60,
+    reset_after: 24 * 60 * 60
+};
+
+/// Catches guessing spread over many chats
+pub const GLOBAL_LOCKOUT_POLICY: LockoutPolicy = LockoutPolicy {
+    threshold: 10,
+    base: 60,
+    max: 60 * 60,
+    reset_after: 60 * 60
+};
+
+impl LockoutState {
+    /// Drops chats whose failures are forgotten, so one-off attempts from many chats do not pile up.
+    pub fn prune(&mut self, now: u64) {
+        self.chats.retain(|_, attempts| !attempts.is_stale(now, &CHAT_LOCKOUT_POLICY));
+    }
+}
+
+impl Attempts {
+    /// Whether the failures are forgotten. Lockouts end before that, as `max` is below `reset_after`.
+    pub fn is_stale(&self, now: u64, policy: &LockoutPolicy) -> bool {
+        now.saturating_sub(self.last_failure) > policy.reset_after
+    }
+
+    /// Seconds left until the lockout ends, if locked.
+    pub fn remaining(&self, now: u64) -> Option<u64> {
+        (self.locked_until > now).then(|| self.locked_until - now)
+    }
+
+    /// Records a failure and returns the lockout duration if it locks.
+    pub fn fail(&mut self, now: u64, policy: &LockoutPolicy) -> Option<u64> {
+        if self.is_stale(now, policy) {
+            self.failures = 0;
+        }
+        self.failures += 1;
+        self.last_failure = now;
+
+        if self.failures < policy.threshold {
+            return None;
+        }
+        let exponent = self.failures - policy.threshold;
+        let duration = policy.base
+            .saturating_mul(2u64.saturating_pow(exponent))
+            .min(policy.max);
+        self.locked_until = now + duration;
+        Some(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockout_doubles_up_to_max() {
+        let mut attempts = Attempts::default();
+        let now = 1_000_000;
+
+        assert_eq!(attempts.fail(now, &CHAT_LOCKOUT_POLICY), None);
+        assert_eq!(attempts.fail(now, &CHAT_LOCKOUT_POLICY), None);
+        assert_eq!(attempts.fail(now, &CHAT_LOCKOUT_POLICY), Some(30));
+        assert_eq!(attempts.remaining(now + 10), Some(20));
+        assert_eq!(attempts.remaining(now + 30), None);
+        assert_eq!(attempts.fail(now + 30, &CHAT_LOCKOUT_POLICY), Some(60));
+        for _ in 0..20 {
+            attempts.fail(now + 100, &CHAT_LOCKOUT_POLICY);
+        }
+        assert_eq!(attempts.remaining(now + 100), Some(60 * 60));
+    }
+
+    #[test]
+    fn failures_reset_after_quiet_period() {
+        let mut attempts = Attempts::default();
+        attempts.fail(1_000_000, &CHAT_LOCKOUT_POLICY);
+        attempts.fail(1_000_000, &CHAT_LOCKOUT_POLICY);
+
+        assert_eq!(attempts.fail(1_000_000 + CHAT_LOCKOUT_POLICY.reset_after + 1, &CHAT_LOCKOUT_POLICY), None);
+        assert_eq!(attempts.failures, 1);
+    }
+
+    #[test]
+    fn prune_drops_stale_chats() {
+        let now = 1_000_000;
+        let mut state = LockoutState::new();
+        state.chats.entry(String::from("telegram:1")).or_default().fail(now, &CHAT_LOCKOUT_POLICY);
+        state.chats.entry(String::from("telegram:2")).or_default().fail(now + 10, &CHAT_LOCKOUT_POLICY);
+
+        state.prune(now + CHAT_LOCKOUT_POLICY.reset_after + 5);
+
+        assert_eq!(state.chats.keys().collect::<Vec<_>>(), vec!["telegram:2"]);
+    }
+}
@@ -1,6 +1,6 @@
 pub mod health;
 
-use crate::domain::config::ServerConfig;
+use crate::domain::config::{RestartMethod, ServerConfig};
 use crate::domain::server::health::HealthCheckMethod;
 
 pub struct Server {
@@ -9,7 +9,8 @@ pub struct Server {
     pub docker_container_name: Option<String>,
     pub health_check_method: HealthCheckMethod,
     pub kill_path: Option<String>,
-    pub log_command: Option<Vec<String>>
+    pub log_command: Option<Vec<String>>,
+    pub restart_method: Option<RestartMethod>
 }
 
 impl Server {
@@ -50,7 +51,8 @@ impl Server {
             docker_container_name: config.docker_container_name,
             health_check_method,
             kill_path: config.kill_path,
-            log_command
+            log_command,
+            restart_method: config.restart
         }
     }
 }
\ No newline at end of file
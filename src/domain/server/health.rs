@@ -6,6 +6,7 @@ pub enum HealthCheckMethod {
     None
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum Health {
     Healthy,
     Unhealthy,
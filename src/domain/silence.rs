@@ -0,0 +1,131 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+use crate::domain::event::format_duration;
+
+/// Alarms muted from chat, e.g. during a deploy.
+#[derive(Serialize, Deserialize, new, Clone, Debug)]
+pub struct SilenceList {
+    #[new(default)]
+    #[serde(default)]
+    pub silences: Vec<Silence>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Silence {
+    pub id: String,
+    /// Event or server name, as told by `kind`
+    pub target: String,
+    /// Silences of older versions are read as server silences
+    #[serde(default)]
+    pub kind: SilenceKind,
+    pub until: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Chat which created the silence, told when it expires
+    pub client_name: String,
+    pub identity: String
+}
+
+/// What the target of a silence names, so a server named like an event is not muted along with it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SilenceKind {
+    Event,
+    #[default]
+    Server
+}
+
+impl Silence {
+    pub fn covers(&self, event_name: &str, server_name: &str) -> bool {
+        match self.kind {
+            SilenceKind::Event => self.target == event_name,
+            SilenceKind::Server => self.target == server_name
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.until <= now
+    }
+
+    /// e.g. "main for 29m 59s (deploy)"
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        let remaining = (self.until - now).to_std().unwrap_or_default();
+        match &self.reason {
+            Some(reason) => format!("{} for {} ({reason})", self.target, format_duration(remaining)),
+            None => format!("{} for {}", self.target, format_duration(remaining))
+        }
+    }
+}
+
+impl SilenceList {
+    pub fn find(&self, event_name: &str, server_name: &str, now: DateTime<Utc>) -> Option<&Silence> {
+        self.silences.iter()
+            .find(|silence| !silence.is_expired(now) && silence.covers(event_name, server_name))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Silence> {
+        let index = self.silences.iter().position(|silence| silence.id == id)?;
+        Some(self.silences.remove(index))
+    }
+
+    /// Removes and returns the silences which ended by `now`.
+    pub fn take_expired(&mut self, now: DateTime<Utc>) -> Vec<Silence> {
+        let (expired, active) = self.silences.drain(..)
+            .partition(|silence| silence.is_expired(now));
+        self.silences = active;
+        expired
+    }
+}
+
+/// Longest silence, so a typo cannot mute alarms for good.
+pub const MAX_DURATION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use super::*;
+
+    fn silence(id: &str, target: &str, kind: SilenceKind, until: DateTime<Utc>) -> Silence {
+        Silence {
+            id: id.to_string(),
+            target: target.to_string(),
+            kind,
+            until,
+            reason: None,
+            client_name: String::from("telegram"),
+            identity: String::from("1")
+        }
+    }
+
+    #[test]
+    fn covers_only_its_kind() {
+        let until = Utc::now();
+        let event = silence("a", "db", SilenceKind::Event, until);
+        let server = silence("b", "db", SilenceKind::Server, until);
+
+        assert!(event.covers("db", "main"));
+        assert!(!event.covers("db-down", "db"));
+        assert!(server.covers("db-down", "db"));
+        assert!(!server.covers("db", "main"));
+    }
+
+    #[test]
+    fn find_and_expire() {
+        let now = Utc::now();
+        let mut list = SilenceList::new();
+        list.silences.push(silence("a", "main", SilenceKind::Server, now + TimeDelta::minutes(10)));
+        list.silences.push(silence("b", "db-down", SilenceKind::Event, now - TimeDelta::minutes(1)));
+
+        assert_eq!(list.find("main-down", "main", now).map(|s| s.id.as_str()), Some("a"));
+        assert_eq!(list.find("db-down", "db", now), None);
+
+        let expired = list.take_expired(now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, "b");
+        assert_eq!(list.silences.len(), 1);
+        assert_eq!(list.remove("a").map(|s| s.id), Some(String::from("a")));
+        assert!(list.remove("a").is_none());
+    }
+}
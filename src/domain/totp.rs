@@ -0,0 +1,11 @@
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+/// Kept across restarts, so an accepted TOTP code cannot be used again.
+#[derive(Serialize, Deserialize, new, Clone, Debug)]
+pub struct TotpState {
+    /// Time step of the last accepted code
+    #[new(default)]
+    #[serde(default)]
+    pub last_step: Option<u64>
+}
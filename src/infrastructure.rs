@@ -2,4 +2,5 @@ pub mod config;
 pub mod cli;
 pub mod client;
 mod server;
-pub mod common;
\ No newline at end of file
+pub mod common;
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::path::PathBuf;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use derive_new::new;
+use log::warn;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use crate::application::audit::AuditLogger;
+use crate::domain::audit::AuditEntry;
+
+/// Appends entries as JSON Lines, one object per line.
+#[derive(new)]
+pub struct JsonlAuditLogger {
+    path: PathBuf
+}
+
+#[async_trait]
+impl AuditLogger for JsonlAuditLogger {
+    async fn record(&self, entry: &AuditEntry) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(directory_path) = self.path.parent() {
+            fs::create_dir_all(directory_path).await?;
+        }
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        // A single write in append mode, so concurrent writers cannot interleave lines
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).await?;
+        file.write_all(line.as_bytes()).await?;
+        // Tokio writes in the background; wait for it so the entry is on disk when this returns
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn entries(&self) -> Result<Vec<AuditEntry>, Box<dyn Error + Send + Sync>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw_string = fs::read_to_string(&self.path).await?;
+        let entries = raw_string.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("[JsonlAuditLogger] Skip malformed line: {e}");
+                    None
+                }
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+pub fn get_audit_logger() -> Result<JsonlAuditLogger, Box<dyn Error + Send + Sync>> {
+    let mut path = home::home_dir()
+        .ok_or(anyhow!("Fail to find home directory"))?;
+    path.push(".watchdog");
+    path.push("audit.jsonl");
+    Ok(JsonlAuditLogger::new(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::audit::AuditOutcome;
+
+    #[tokio::test]
+    async fn record_appends_lines() {
+        let path = std::env::temp_dir().join(format!("sw-audit-{}", uuid::Uuid::new_v4())).join("audit.jsonl");
+        let audit_logger = JsonlAuditLogger::new(path.clone());
+        assert!(audit_logger.entries().await.unwrap().is_empty());
+
+        for command in ["HealthCheckAll", r#"Kill("main")"#] {
+            let entry = AuditEntry::new(String::from("telegram"), String::from("42"), None, command.to_string(), AuditOutcome::Success, None);
+            audit_logger.record(&entry).await.unwrap();
+        }
+
+        let raw_string = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(raw_string.lines().count(), 2);
+        let entries = audit_logger.entries().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].command, r#"Kill("main")"#);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -3,6 +3,10 @@ mod client;
 mod util;
 mod common;
 mod password;
+mod chat;
+mod role;
+mod audit;
+mod maintenance;
 pub mod event;
 
 pub use common::*;
\ No newline at end of file
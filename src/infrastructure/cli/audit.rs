@@ -0,0 +1,51 @@
+use clap::Subcommand;
+use log::{debug, trace};
+use crate::application::audit::AuditLogger;
+
+#[derive(Subcommand)]
+#[derive(Debug)]
+pub enum AuditCommands {
+    /// Prints the latest entries
+    Tail {
+        #[arg(short = 'n', long, default_value_t = 20)]
+        lines: usize
+    },
+    /// Prints entries containing the query (case-insensitive), e.g. a chat id, server name or "denied"
+    Search {
+        query: String,
+        #[arg(short = 'n', long, default_value_t = 100)]
+        lines: usize
+    }
+}
+
+impl AuditCommands {
+    pub async fn run(&self, audit_logger: Box<dyn AuditLogger>) {
+        trace!("audit command start: {:?}", &self);
+        let entries = match audit_logger.entries().await {
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("Fail to read audit log: {e}");
+                return;
+            }
+        };
+
+        let entries: Vec<_> = match self {
+            AuditCommands::Tail { .. } => entries,
+            AuditCommands::Search { query, .. } => {
+                debug!("search audit log: {}", query);
+                entries.into_iter().filter(|entry| entry.matches(query.as_str())).collect()
+            }
+        };
+        let lines = match self {
+            AuditCommands::Tail { lines } | AuditCommands::Search { lines, .. } => *lines
+        };
+
+        if entries.is_empty() {
+            println!("No audit entries");
+        }
+        for entry in entries.iter().skip(entries.len().saturating_sub(lines)) {
+            println!("{entry}");
+        }
+        trace!("audit command end");
+    }
+}
@@ -0,0 +1,63 @@
+use clap::Subcommand;
+use log::{debug, trace};
+use crate::application::config::{AuthUseCase, EventSubscribeUseCase};
+
+#[derive(Subcommand)]
+#[derive(Debug)]
+pub enum ChatCommands {
+    /// Registers a chat without /register, e.g. a webhook or email recipient
+    Add {
+        client_name: String,
+        identity: String
+    },
+    /// Lists registered chats
+    List,
+    /// Removes a chat and its alarms
+    Revoke {
+        chat_id: String
+    }
+}
+
+impl ChatCommands {
+    pub async fn run(&self, mut auth_use_case: Box<dyn AuthUseCase>, event_subscribe_use_case: Box<dyn EventSubscribeUseCase>) {
+        trace!("chat command start: {:?}", &self);
+        match self {
+            ChatCommands::Add { client_name, identity } => {
+                debug!("add chat: {} {}", client_name, identity);
+                match auth_use_case.register(client_name.clone(), identity.clone()).await {
+                    Ok(id) => println!("Chat registered: {id}"),
+                    Err(e) => println!("Fail to register chat: {e}")
+                }
+            },
+            ChatCommands::List => {
+                debug!("list chat");
+                let chats = match auth_use_case.list_chats().await {
+                    Ok(chats) => chats,
+                    Err(e) => {
+                        println!("Fail to list chats: {e}");
+                        return;
+                    }
+                };
+                println!("--- Chat List ---");
+                if chats.is_empty() {
+                    println!("Empty Chat");
+                }
+                for chat in chats {
+                    println!("{} {}", chat.id, chat.describe());
+                }
+            },
+            ChatCommands::Revoke { chat_id } => {
+                debug!("revoke chat: {}", chat_id);
+                if let Err(e) = auth_use_case.revoke(chat_id.as_str()).await {
+                    println!("Fail to revoke chat: {e}");
+                    return;
+                }
+                match event_subscribe_use_case.unsubscribe_all(chat_id.clone()).await {
+                    Ok(_) => println!("Chat revoked: {chat_id}"),
+                    Err(e) => println!("Chat revoked, but fail to remove its alarms: {e}")
+                }
+            }
+        }
+        trace!("chat command end");
+    }
+}
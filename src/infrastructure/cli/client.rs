@@ -1,8 +1,9 @@
+use std::collections::HashMap;
 use clap::Subcommand;
 use log::{debug, trace};
 use crate::application::config::ClientConfigUseCase;
-use crate::domain::config::ClientConfig;
-use crate::infrastructure::cli::util::{read_string, read_string_option, FormatChecker};
+use crate::domain::config::{ClientConfig, SmtpConfig, SmtpSecurity, WebhookReceiverConfig};
+use crate::infrastructure::cli::util::{read_int, read_string, read_string_option, FormatChecker};
 
 #[derive(Subcommand)]
 #[derive(Debug)]
@@ -18,11 +19,76 @@ impl ClientCommands {
             ClientCommands::Add => {
                 debug!("add client");
                 let name = read_string("Name", FormatChecker::Name).await;
-                let kind = read_string("kind (ex: telegram)", FormatChecker::Name).await;
+                let kind = read_string("kind (telegram, slack, discord, matrix, webhook, email, console)", FormatChecker::Name).await;
                 let token = read_string_option("Token", FormatChecker::NotAllowWhitespace).await;
 
                 let client = match kind.as_str() {
-                    "telegram" => ClientConfig::new_telegram(name.as_str(), token.unwrap().as_str()),
+                    "telegram" => {
+                        match read_string_option("public webhook url (empty: long polling)", FormatChecker::NotAllowWhitespace).await {
+                            Some(url) => {
+                                let listen_addr = read_string("listen address (ex: 0.0.0.0:8080)", FormatChecker::NotAllowWhitespace).await;
+                                let secret_token = read_string_option("webhook secret token (empty: generated on each run)", FormatChecker::NotAllowWhitespace).await;
+                                ClientConfig::new_telegram_webhook(name.as_str(), token.unwrap().as_str(), WebhookReceiverConfig { url, listen_addr, secret_token })
+                            },
+                            None => ClientConfig::new_telegram(name.as_str(), token.unwrap().as_str())
+                        }
+                    },
+                    "slack" => {
+                        let app_token = read_string("App-level token (xapp-...)", FormatChecker::NotAllowWhitespace).await;
+                        ClientConfig::new_slack(name.as_str(), token.unwrap().as_str(), app_token.as_str())
+                    },
+                    "discord" => {
+                        let allowed_channels = read_string_option("allowed channel ids (comma separated)", FormatChecker::NotAllowWhitespace).await
+                            .map(|channels| channels.split(',').map(String::from).collect());
+                        ClientConfig::new_discord(name.as_str(), token.unwrap().as_str(), allowed_channels)
+                    },
+                    "matrix" => {
+                        let homeserver_url = read_string("homeserver url (ex: https://matrix.example.org)", FormatChecker::NotAllowWhitespace).await;
+                        ClientConfig::new_matrix(name.as_str(), homeserver_url.as_str(), token.unwrap().as_str())
+                    },
+                    "webhook" => {
+                        let url = read_string("webhook url", FormatChecker::NotAllowWhitespace).await;
+                        let mut headers = HashMap::new();
+                        while let Some(header) = read_string_option("header (ex: Authorization: Bearer abc)", FormatChecker::None).await {
+                            match header.split_once(':') {
+                                Some((key, value)) => { headers.insert(key.trim().to_string(), value.trim().to_string()); },
+                                None => println!("invalid input")
+                            }
+                        }
+                        let headers = if headers.is_empty() { None } else { Some(headers) };
+                        let body_template = read_string_option(r#"body template (default: {"text": "{{message}}"})"#, FormatChecker::Json).await;
+                        ClientConfig::new_webhook(name.as_str(), url.as_str(), headers, body_template)
+                    },
+                    "email" => {
+                        let host = read_string("smtp host", FormatChecker::NotAllowWhitespace).await;
+                        let port = loop {
+                            match u16::try_from(read_int("smtp port (ex: 465, 587)").await) {
+                                Ok(port) if port != 0 => break port,
+                                _ => println!("invalid input")
+                            }
+                        };
+                        let security = loop {
+                            match read_string("security (tls, starttls, plaintext)", FormatChecker::NotAllowWhitespace).await.parse() {
+                                Ok(security) => break security,
+                                Err(e) => println!("{e}")
+                            }
+                        };
+                        // Credentials are never sent over a plaintext connection
+                        let (username, password) = if security == SmtpSecurity::Plaintext {
+                            (None, None)
+                        } else {
+                            (
+                                read_string_option("smtp username", FormatChecker::NotAllowWhitespace).await,
+                                read_string_option("smtp password", FormatChecker::None).await
+                            )
+                        };
+                        let from = read_string("from address (ex: Watchdog <watchdog@example.org>)", FormatChecker::None).await;
+                        ClientConfig::new_email(name.as_str(), SmtpConfig { host, port, security: Some(security), starttls: false, username, password, from })
+                    },
+                    "console" => {
+                        let socket_path = read_string_option("unix socket path (empty: stdin)", FormatChecker::NotAllowWhitespace).await;
+                        ClientConfig::new_console(name.as_str(), socket_path)
+                    },
                     _ => {
                         println!("kind({kind}) is not available");
                         return;
@@ -4,20 +4,26 @@ use clap::{Parser, Subcommand};
 use log::{debug, trace};
 use tokio::sync::mpsc;
 use crate::application::client::ClientLoader;
-use crate::application::event::checker::{GeneralEventChecker, HealthEventChecker, LogEventChecker};
+use crate::application::event::checker::{GeneralEventChecker, HealthEventChecker, HealthTransitionEventChecker, LogEventChecker};
+use crate::application::event::escalation::EscalationWorker;
 use crate::application::event::receiver::EventManager;
 use crate::application::handler::{GeneralHandler, MessageHandler};
 use crate::application::worker::WorkerRunner;
 use crate::domain::chat::ChatList;
 use crate::domain::config::{Config, EventSubscribeList};
 use crate::domain::file_accessor::FileAccessor;
+use crate::infrastructure::audit::get_audit_logger;
+use crate::infrastructure::cli::audit::AuditCommands;
+use crate::infrastructure::cli::chat::ChatCommands;
 use crate::infrastructure::cli::client::ClientCommands;
 use crate::infrastructure::cli::event::EventCommands;
+use crate::infrastructure::cli::maintenance::MaintenanceCommands;
 use crate::infrastructure::cli::password::PasswordCommands;
+use crate::infrastructure::cli::role::RoleCommands;
 use crate::infrastructure::cli::server::ServerCommands;
 use crate::infrastructure::client::{ClientManager, MessageAdapter};
-use crate::infrastructure::common::file_accessor::{get_chat_list_file_accessor, get_config_file_accessor, get_event_subscribe_file_accessor};
-use crate::infrastructure::config::{ClientConfigAdapter, EventConfigAdapter, ServerConfigAdapter};
+use crate::infrastructure::common::file_accessor::{get_chat_list_file_accessor, get_config_file_accessor, get_event_subscribe_file_accessor, get_alert_file_accessor, get_lockout_file_accessor, get_silence_file_accessor, get_totp_state_file_accessor};
+use crate::infrastructure::config::{AlertAdapter, ClientConfigAdapter, EventConfigAdapter, LockoutAdapter, MaintenanceAdapter, ServerConfigAdapter, SilenceAdapter};
 use crate::infrastructure::config::auth::AuthAdapter;
 use crate::infrastructure::server::{ConfigServerRepository, GeneralServerManager};
 
@@ -48,6 +54,22 @@ pub enum Commands {
         #[command(subcommand)]
         command: EventCommands
     },
+    Chat {
+        #[command(subcommand)]
+        command: ChatCommands
+    },
+    Role {
+        #[command(subcommand)]
+        command: RoleCommands
+    },
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands
+    },
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommands
+    },
     Run
 }
 
@@ -58,10 +80,11 @@ impl Commands {
         match self {
             Commands::Password { command } => {
                 let chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync> = Arc::new(get_chat_list_file_accessor());
-                let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor);
+                let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor, Arc::new(get_totp_state_file_accessor()));
                 auth_adapter.init().await;
                 let auth_config = Box::new(auth_adapter);
-                command.run(auth_config).await
+                let lockout_config = Box::new(LockoutAdapter::new(Arc::new(get_lockout_file_accessor())));
+                command.run(auth_config, lockout_config).await
             }
             Commands::Server { command } => {
                 debug!("server command");
@@ -84,11 +107,39 @@ impl Commands {
                 let subscribe_file_accessor: Arc<dyn FileAccessor<EventSubscribeList> + Send + Sync> = Arc::new(get_event_subscribe_file_accessor());
 
                 let event_config = EventConfigAdapter::new(
+                    config_file_accessor.clone(),
+                    subscribe_file_accessor.clone()
+                );
+                let event_subscribe = EventConfigAdapter::new(
                     config_file_accessor.clone(),
                     subscribe_file_accessor
                 );
-                let event_config = Box::new(event_config);
-                command.run(event_config).await
+                command.run(Box::new(event_config), Box::new(event_subscribe)).await
+            },
+            Commands::Chat { command } => {
+                debug!("chat command");
+                let chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync> = Arc::new(get_chat_list_file_accessor());
+                let subscribe_file_accessor: Arc<dyn FileAccessor<EventSubscribeList> + Send + Sync> = Arc::new(get_event_subscribe_file_accessor());
+                let auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor, Arc::new(get_totp_state_file_accessor()));
+                let event_subscribe = EventConfigAdapter::new(config_file_accessor.clone(), subscribe_file_accessor);
+                command.run(Box::new(auth_adapter), Box::new(event_subscribe)).await
+            },
+            Commands::Role { command } => {
+                debug!("role command");
+                let chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync> = Arc::new(get_chat_list_file_accessor());
+                let auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor, Arc::new(get_totp_state_file_accessor()));
+                command.run(Box::new(auth_adapter)).await
+            },
+            Commands::Audit { command } => {
+                debug!("audit command");
+                match get_audit_logger() {
+                    Ok(audit_logger) => command.run(Box::new(audit_logger)).await,
+                    Err(e) => println!("Fail to open audit log: {e}")
+                }
+            },
+            Commands::Maintenance { command } => {
+                debug!("maintenance command");
+                command.run(Box::new(MaintenanceAdapter::new(config_file_accessor.clone()))).await
             },
             Commands::Run => {
                 debug!("run command");
@@ -104,10 +155,14 @@ impl Commands {
                 );
                 let _ = client_manager.load_clients().await;
 
-                let message_gateway = Arc::new(MessageAdapter::new(Arc::new(client_manager.clone())));
+                let mut message_adapter = MessageAdapter::new(Arc::new(client_manager.clone()));
+                if let Ok(Config { attachment_threshold: Some(attachment_threshold), .. }) = config_file_accessor.read().await {
+                    message_adapter = message_adapter.with_attachment_threshold(attachment_threshold);
+                }
+                let message_gateway = Arc::new(message_adapter);
                 let mut rx = client_manager.run().await;
 
-                let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor.clone());
+                let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), chat_list_file_accessor.clone(), Arc::new(get_totp_state_file_accessor()));
                 auth_adapter.init().await;
 
                 let mut server_repository = ConfigServerRepository::new(
@@ -121,25 +176,49 @@ impl Commands {
                 ));
 
                 let server_manager = Arc::new(GeneralServerManager::new(Box::new(server_repository)));
+                let silence_adapter = Arc::new(SilenceAdapter::new(Arc::new(get_silence_file_accessor())));
+                let maintenance_adapter = Arc::new(MaintenanceAdapter::new(config_file_accessor.clone()));
+                let alert_adapter = Arc::new(AlertAdapter::new(Arc::new(get_alert_file_accessor())));
 
                 let mut handler = GeneralHandler::new(
                     message_gateway.clone(),
                     server_manager.clone(),
                     Box::new(auth_adapter),
+                    Box::new(LockoutAdapter::new(Arc::new(get_lockout_file_accessor()))),
                     event_config_adapter.clone(),
-                    event_config_adapter.clone()
+                    event_config_adapter.clone(),
+                    silence_adapter.clone(),
+                    maintenance_adapter.clone(),
+                    alert_adapter.clone(),
+                    Arc::new(get_audit_logger().expect("Fail to find home directory"))
                 );
 
                 let (tx, rx_event) = mpsc::channel(32);
-                let event_manager = EventManager::new(
+                let mut event_manager = EventManager::new(
                     rx_event,
                     message_gateway.clone(),
-                    chat_list_file_accessor,
-                    subscribe_file_accessor
+                    chat_list_file_accessor.clone(),
+                    subscribe_file_accessor,
+                    silence_adapter.clone(),
+                    maintenance_adapter.clone(),
+                    alert_adapter.clone()
+                );
+                let escalation_worker = EscalationWorker::new(
+                    message_gateway.clone(),
+                    alert_adapter,
+                    event_config_adapter.clone(),
+                    silence_adapter,
+                    maintenance_adapter,
+                    chat_list_file_accessor
                 );
+                if let Ok(Config { digest: Some(digest_config), .. }) = config_file_accessor.read().await {
+                    event_manager = event_manager.with_digest(&digest_config);
+                }
 
                 {
-                    worker_runner.lock().unwrap().run(Box::new(event_manager));
+                    let mut worker_runner = worker_runner.lock().unwrap();
+                    worker_runner.run(Box::new(event_manager));
+                    worker_runner.run(Box::new(escalation_worker));
                 }
 
                 let event_checker = GeneralEventChecker::new(
@@ -147,6 +226,7 @@ impl Commands {
                     server_manager.clone(),
                     tx,
                     Box::new(HealthEventChecker::new()),
+                    Box::new(HealthTransitionEventChecker::new()),
                     Box::new(LogEventChecker::new())
                 );
 
@@ -1,34 +1,84 @@
 use clap::Subcommand;
 use log::{debug, trace};
-use crate::application::config::EventConfigUseCase;
-use crate::domain::config::EventConfig;
-use crate::infrastructure::cli::util::{read_string, FormatChecker};
+use crate::application::config::{EventConfigUseCase, EventSubscribeUseCase};
+use std::time::Duration;
+use crate::domain::alert::ALERT_RETENTION;
+use crate::domain::config::{EscalationLevel, EventConfig, MatchMode};
+use crate::domain::event::{format_duration, parse_duration, MAX_WINDOW};
+use crate::infrastructure::cli::util::{read_string, read_string_option, FormatChecker};
 
 #[derive(Subcommand)]
 #[derive(Debug)]
 pub enum EventCommands {
     Add,
     List,
-    Remove { name: String }
+    Remove { name: String },
+    /// Subscribes a registered chat (see `sw chat add`) to an event
+    Subscribe { name: String, chat_id: String },
+    Unsubscribe { name: String, chat_id: String }
 }
 
 impl EventCommands {
-    pub async fn run(&self, event_config_adapter: Box<dyn EventConfigUseCase>) {
+    pub async fn run(&self, event_config_adapter: Box<dyn EventConfigUseCase>, event_subscribe_adapter: Box<dyn EventSubscribeUseCase>) {
         trace!("event command start: {:?}", &self);
         match self {
             EventCommands::Add => {
                 debug!("add event");
                 println!("--- Add Event ---");
                 let name = read_string("name", FormatChecker::Name).await;
-                let event_type = read_string("type (logs, health)", FormatChecker::Name).await;
+                let event_type = read_string("type (logs, health, health-transition)", FormatChecker::Name).await;
                 let target = read_string("target server name", FormatChecker::Name).await;
-                let keyword = read_string("keyword", FormatChecker::None).await;
+                // Transitions report every change of health, so there is nothing to match
+                let (match_mode, keyword, negate) = if event_type == "health-transition" {
+                    (MatchMode::default(), String::new(), false)
+                } else {
+                    let match_mode = loop {
+                        match read_string_option("match mode (substring, case-insensitive, regex)", FormatChecker::None).await {
+                            None => break MatchMode::default(),
+                            Some(value) => match value.parse() {
+                                Ok(match_mode) => break match_mode,
+                                Err(e) => println!("{e}")
+                            }
+                        }
+                    };
+                    let keyword = match match_mode {
+                        MatchMode::Regex => read_string("keyword (regex, e.g. ERROR (?P<code>\\w+))", FormatChecker::Regex).await,
+                        MatchMode::Substring | MatchMode::CaseInsensitive => read_string("keyword", FormatChecker::None).await
+                    };
+                    // On logs, every line without the keyword would alarm
+                    let negate = event_type == "health" && loop {
+                        match read_string_option("negate, alarm when the keyword does not match (yes, no)", FormatChecker::None).await.as_deref() {
+                            None | Some("no") => break false,
+                            Some("yes") => break true,
+                            Some(_) => println!("invalid input")
+                        }
+                    };
+                    (match_mode, keyword, negate)
+                };
+                let message = read_string_option("message template, e.g. {{server}}: {{code}}", FormatChecker::None).await;
+                let cooldown = read_window("cooldown seconds, collapsing any further alarm").await;
+                let dedup_window = read_window("dedup window seconds, collapsing alarms with the same text").await;
+                let escalation = loop {
+                    match read_string_option("escalation while not acknowledged, delays under 1d, e.g. 10m=<chat_id>,<chat_id> 30m=<chat_id>", FormatChecker::None).await {
+                        None => break Vec::new(),
+                        Some(value) => match parse_escalation(value.as_str()) {
+                            Some(escalation) => break escalation,
+                            None => println!("invalid input")
+                        }
+                    }
+                };
 
                 let config = EventConfig {
                     r#type: event_type,
                     name,
                     target,
                     keyword,
+                    match_mode,
+                    negate,
+                    message,
+                    cooldown,
+                    dedup_window,
+                    escalation
                 };
                 debug!("new event config: {:?}", &config);
                 event_config_adapter.add_event(config).await.unwrap();
@@ -45,11 +95,15 @@ impl EventCommands {
                 } else {
                     for event in events {
                         println!(
-                            "=========\nName: {}\nType: {}\nTarget: {}\nKeyword: {}\n\n",
+                            "=========\nName: {}\nType: {}\nTarget: {}\nKeyword: {}\nMatch: {}{}\nMessage: {}\nEscalation: {}\n\n",
                             event.name,
                             event.r#type,
                             event.target,
-                            event.keyword
+                            event.keyword,
+                            event.match_mode,
+                            if event.negate { " (negated)" } else { "" },
+                            event.message.as_deref().unwrap_or("None"),
+                            describe_escalation(&event.escalation)
                         );
                     }
                 }
@@ -58,8 +112,66 @@ impl EventCommands {
                 debug!("remove event: {}", name);
                 event_config_adapter.remove_event(name.clone()).await.unwrap();
                 println!("Event '{}' removed.", name);
+            },
+            EventCommands::Subscribe { name, chat_id } => {
+                debug!("subscribe event: {} {}", name, chat_id);
+                match event_subscribe_adapter.subscribe(chat_id.clone(), name.clone()).await {
+                    Ok(_) => println!("Chat '{}' subscribed to '{}'.", chat_id, name),
+                    Err(e) => println!("Fail to subscribe: {e}")
+                }
+            },
+            EventCommands::Unsubscribe { name, chat_id } => {
+                debug!("unsubscribe event: {} {}", name, chat_id);
+                event_subscribe_adapter.unsubscribe(chat_id.clone(), name.clone()).await.unwrap();
+                println!("Chat '{}' unsubscribed from '{}'.", chat_id, name);
             }
         }
         trace!("event command end");
     }
-}
\ No newline at end of file
+}
+
+/// Parses levels like `10m=chat-a,chat-b 30m=chat-c`, ordered by delay. Delays must be shorter than a day.
+/// Reads optional seconds, up to a day.
+async fn read_window(prompt: &str) -> Option<u64> {
+    let prompt = format!("{prompt}, up to {}", MAX_WINDOW.as_secs());
+    loop {
+        let value = read_string_option(prompt.as_str(), FormatChecker::Number).await?;
+        match value.parse() {
+            Ok(seconds) if seconds <= MAX_WINDOW.as_secs() => return Some(seconds),
+            _ => println!("invalid input")
+        }
+    }
+}
+
+fn parse_escalation(text: &str) -> Option<Vec<EscalationLevel>> {
+    let mut levels = text.split_whitespace()
+        .map(|level| {
+            let (delay, chat_ids) = level.split_once('=')?;
+            let chat_ids: Vec<String> = chat_ids.split(',')
+                .filter(|chat_id| !chat_id.is_empty())
+                .map(str::to_string)
+                .collect();
+            if chat_ids.is_empty() {
+                return None;
+            }
+            // Alerts are pruned before a longer delay could pass
+            let delay = parse_duration(delay)?.as_secs();
+            if delay >= ALERT_RETENTION.num_seconds() as u64 {
+                return None;
+            }
+            Some(EscalationLevel { delay, chat_ids })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    levels.sort_by_key(|level| level.delay);
+    Some(levels)
+}
+
+fn describe_escalation(escalation: &[EscalationLevel]) -> String {
+    if escalation.is_empty() {
+        return String::from("None");
+    }
+    escalation.iter()
+        .map(|level| format!("after {}: {}", format_duration(Duration::from_secs(level.delay)), level.chat_ids.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
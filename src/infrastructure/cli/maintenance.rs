@@ -0,0 +1,75 @@
+use clap::Subcommand;
+use log::{debug, trace};
+use crate::application::config::MaintenanceUseCase;
+use crate::domain::config::{MaintenanceConfig, MAX_MAINTENANCE_DURATION};
+use crate::domain::event::{format_duration, parse_duration};
+use crate::infrastructure::cli::util::{read_string, FormatChecker};
+
+#[derive(Subcommand)]
+#[derive(Debug)]
+pub enum MaintenanceCommands {
+    /// Adds a weekly window in which alarms of a server are suppressed
+    Add,
+    List,
+    Remove { name: String }
+}
+
+impl MaintenanceCommands {
+    pub async fn run(&self, maintenance_use_case: Box<dyn MaintenanceUseCase>) {
+        trace!("maintenance command start: {:?}", &self);
+        match self {
+            MaintenanceCommands::Add => {
+                println!("--- Add Maintenance Window ---");
+                let name = read_string("name", FormatChecker::Name).await;
+                let server_name = read_string("server name", FormatChecker::Name).await;
+                let schedule = loop {
+                    match read_string("schedule in local time (e.g. daily 03:00, sat,sun 02:30)", FormatChecker::None).await.parse() {
+                        Ok(schedule) => break schedule,
+                        Err(e) => println!("{e}")
+                    }
+                };
+                let duration = loop {
+                    match parse_duration(read_string("duration, up to 7d (e.g. 30m, 2h)", FormatChecker::NotAllowWhitespace).await.as_str()) {
+                        Some(duration) if duration.as_secs() <= MAX_MAINTENANCE_DURATION => break duration,
+                        _ => println!("invalid input")
+                    }
+                };
+
+                let maintenance_config = MaintenanceConfig {
+                    name,
+                    server_name,
+                    schedule,
+                    duration: duration.as_secs()
+                };
+                debug!("new maintenance config: {:?}", &maintenance_config);
+                match maintenance_use_case.add_maintenance(maintenance_config).await {
+                    Ok(_) => println!("Maintenance window added."),
+                    Err(e) => println!("Fail to add maintenance window: {e}")
+                }
+            },
+            MaintenanceCommands::List => {
+                let windows = maintenance_use_case.list_maintenance().await.unwrap();
+                println!("--- Maintenance Window List ---");
+                if windows.is_empty() {
+                    println!("Empty Maintenance Window");
+                }
+                for window in windows {
+                    println!(
+                        "=========\nName: {}\nServer: {}\nSchedule: {}\nDuration: {}\n",
+                        window.name,
+                        window.server_name,
+                        window.schedule,
+                        format_duration(std::time::Duration::from_secs(window.duration))
+                    );
+                }
+            },
+            MaintenanceCommands::Remove { name } => {
+                match maintenance_use_case.remove_maintenance(name.as_str()).await {
+                    Ok(_) => println!("Maintenance window '{}' removed.", name),
+                    Err(e) => println!("Fail to remove maintenance window: {e}")
+                }
+            }
+        }
+        trace!("maintenance command end");
+    }
+}
@@ -1,26 +1,80 @@
 use clap::Subcommand;
-use crate::application::config::AuthUseCase;
+use crate::application::config::{AuthUseCase, LockoutUseCase};
+use crate::infrastructure::cli::util::read_password;
+use crate::infrastructure::common::totp;
 
 #[derive(Subcommand)]
 #[derive(Debug)]
 pub enum PasswordCommands {
-    Set {
-        password: String
-    },
-    Clear
+    /// Prompts for the password without echo, so it stays out of shell history
+    Set,
+    Clear,
+    /// Lifts /register lockouts, e.g. when guessing from other chats keeps you out
+    Unlock,
+    /// Second factor for /register from an authenticator app
+    Totp {
+        #[command(subcommand)]
+        command: TotpCommands
+    }
+}
+
+#[derive(Subcommand)]
+#[derive(Debug)]
+pub enum TotpCommands {
+    /// Creates a new secret and prints it for enrollment, replacing any previous one
+    Enable,
+    Disable
 }
 
 impl PasswordCommands {
 
-    pub async fn run(&self, auth_use_case: Box<dyn  AuthUseCase>) {
+    pub async fn run(&self, auth_use_case: Box<dyn  AuthUseCase>, lockout_use_case: Box<dyn LockoutUseCase>) {
         match self {
-            PasswordCommands::Set { password } => {
-                println!("Password set");
-                let _ = auth_use_case.set_password(Some(password.clone())).await;
+            PasswordCommands::Set => {
+                let password = read_password("password").await;
+                if read_password("password again").await != password {
+                    println!("Passwords do not match");
+                    return;
+                }
+                match auth_use_case.set_password(Some(password)).await {
+                    Ok(_) => println!("Password set"),
+                    Err(e) => println!("Fail to set password: {e}")
+                }
             },
             PasswordCommands::Clear => {
-                let _ = auth_use_case.set_password(None).await;
-                println!("Password removed");
+                match auth_use_case.set_password(None).await {
+                    Ok(_) if auth_use_case.totp_required() => println!("Password removed, TOTP disabled"),
+                    Ok(_) => println!("Password removed"),
+                    Err(e) => println!("Fail to remove password: {e}")
+                }
+            },
+            PasswordCommands::Unlock => {
+                match lockout_use_case.reset().await {
+                    Ok(_) => println!("Lockouts lifted"),
+                    Err(e) => println!("Fail to lift lockouts: {e}")
+                }
+            },
+            PasswordCommands::Totp { command: TotpCommands::Enable } => {
+                if !auth_use_case.password_required() {
+                    println!("Set a password first: sw password set");
+                    return;
+                }
+                let secret = totp::generate_secret();
+                match auth_use_case.set_totp_secret(Some(secret.clone())).await {
+                    Ok(_) => {
+                        println!("TOTP enabled. Add this secret to your authenticator app:");
+                        println!("Secret: {secret}");
+                        println!("URI: {}", totp::provisioning_uri(secret.as_str(), "watchdog"));
+                        println!("Register with: /register <password> <code>");
+                    },
+                    Err(e) => println!("Fail to enable TOTP: {e}")
+                }
+            },
+            PasswordCommands::Totp { command: TotpCommands::Disable } => {
+                match auth_use_case.set_totp_secret(None).await {
+                    Ok(_) => println!("TOTP disabled"),
+                    Err(e) => println!("Fail to disable TOTP: {e}")
+                }
             }
         }
     }
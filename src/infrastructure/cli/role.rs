@@ -0,0 +1,61 @@
+use clap::Subcommand;
+use log::{debug, trace};
+use crate::application::config::AuthUseCase;
+use crate::domain::chat::Role;
+
+#[derive(Subcommand)]
+#[derive(Debug)]
+pub enum RoleCommands {
+    /// Grants a role (viewer, operator, admin) to a chat
+    Grant {
+        chat_id: String,
+        role: Role,
+        /// Limits the chat to this server (repeatable)
+        #[arg(long = "server")]
+        servers: Vec<String>,
+        /// Removes the server limit
+        #[arg(long, conflicts_with = "servers")]
+        all_servers: bool
+    },
+    /// Resets a chat to the viewer role
+    Revoke {
+        chat_id: String
+    }
+}
+
+impl RoleCommands {
+    pub async fn run(&self, mut auth_use_case: Box<dyn AuthUseCase>) {
+        trace!("role command start: {:?}", &self);
+        match self {
+            RoleCommands::Grant { chat_id, role, servers, all_servers } => {
+                debug!("grant role: {} {}", chat_id, role);
+                if let Err(e) = auth_use_case.set_role(chat_id.as_str(), *role).await {
+                    println!("Fail to grant role: {e}");
+                    return;
+                }
+
+                let servers = if *all_servers {
+                    Some(None)
+                } else if !servers.is_empty() {
+                    Some(Some(servers.clone()))
+                } else {
+                    None
+                };
+                if let Some(servers) = servers
+                    && let Err(e) = auth_use_case.set_servers(chat_id.as_str(), servers).await {
+                    println!("Fail to set servers: {e}");
+                    return;
+                }
+                println!("Role granted: {role}");
+            },
+            RoleCommands::Revoke { chat_id } => {
+                debug!("revoke role: {}", chat_id);
+                match auth_use_case.set_role(chat_id.as_str(), Role::Viewer).await {
+                    Ok(_) => println!("Role revoked: {}", Role::Viewer),
+                    Err(e) => println!("Fail to revoke role: {e}")
+                }
+            }
+        }
+        trace!("role command end");
+    }
+}
@@ -1,7 +1,7 @@
 use clap::Subcommand;
 use log::{debug, trace};
 use crate::application::config::ServerConfigUseCase;
-use crate::domain::config::ServerConfig;
+use crate::domain::config::{RestartMethod, ServerConfig};
 use crate::domain::server::Server;
 use crate::infrastructure::cli::util::{read_string, read_string_option, FormatChecker};
 
@@ -25,8 +25,23 @@ impl ServerCommands {
                 let health_check_path = read_string_option("health check path", FormatChecker::NotAllowWhitespace).await;
                 let kill_path = read_string_option("kill path", FormatChecker::NotAllowWhitespace).await;
                 let log_command = read_string_option("log command", FormatChecker::None).await;
+                let restart = loop {
+                    match read_string_option("restart method (docker, systemd, command)", FormatChecker::Name).await.as_deref() {
+                        None => break None,
+                        Some("docker") => break Some(RestartMethod::Docker),
+                        Some("systemd") => {
+                            let unit = read_string("systemd unit", FormatChecker::NotAllowWhitespace).await;
+                            break Some(RestartMethod::Systemd { unit });
+                        },
+                        Some("command") => {
+                            let command = read_string("restart command", FormatChecker::None).await;
+                            break Some(RestartMethod::Command { command });
+                        },
+                        Some(_) => println!("invalid input")
+                    }
+                };
 
-                let config = ServerConfig::new(name, base_url, docker_container_name, health_check_path, kill_path, log_command);
+                let config = ServerConfig::new(name, base_url, docker_container_name, health_check_path, kill_path, log_command, restart);
                 debug!("new server config: {:?}", &config);
                 let _ = server_config_adapter.add_server(config).await;
             },
@@ -50,14 +65,22 @@ impl ServerCommands {
                             None => "None".to_string()
                         };
 
+                        let restart = match server.restart_method.as_ref() {
+                            Some(RestartMethod::Docker) => "docker restart".to_string(),
+                            Some(RestartMethod::Systemd { unit }) => format!("systemctl restart {unit}"),
+                            Some(RestartMethod::Command { command }) => command.clone(),
+                            None => "None".to_string()
+                        };
+
                         println!(
-                            "=========\nName: {}\nBASE URL: {}\nDocker Container Name: {}\nKill URL: {}\nHealth Check URL: {}\nLog command: {}\n\n",
+                            "=========\nName: {}\nBASE URL: {}\nDocker Container Name: {}\nKill URL: {}\nHealth Check URL: {}\nLog command: {}\nRestart: {}\n\n",
                             server.name,
                             server.base_url.as_deref().unwrap_or("None"),
                             server.docker_container_name.as_deref().unwrap_or("None"),
                             server.get_kill_url().as_deref().unwrap_or("None"),
                             server.get_health_check_url().as_deref().unwrap_or("None"),
-                            command
+                            command,
+                            restart
                         );
                     }
                 }
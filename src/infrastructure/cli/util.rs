@@ -47,6 +47,20 @@ pub async fn read_string(name: &str, format_checker: FormatChecker) -> String {
     }
 }
 
+/// Reads a non-empty line from the terminal without echoing it.
+pub async fn read_password(name: &str) -> String {
+    loop {
+        let prompt = format!("--- type {name} ---\n");
+        let value = tokio::task::spawn_blocking(move || rpassword::prompt_password(prompt)).await;
+        match value {
+            Ok(Ok(value)) if !value.is_empty() => return value,
+            Ok(Ok(_)) => println!("invalid input"),
+            Ok(Err(e)) => panic!("Fail to read {name}: {e}"),
+            Err(e) => panic!("Fail to read {name}: {e}")
+        }
+    }
+}
+
 pub async fn read_int(name: &str) -> i32 {
     let mut reader = BufReader::new(stdin());
     loop {
@@ -75,6 +89,9 @@ pub enum FormatChecker {
     BaseUrl,
     Name,
     NotAllowWhitespace,
+    Json,
+    Regex,
+    Number,
     None,
 }
 
@@ -94,6 +111,11 @@ impl FormatChecker {
             FormatChecker::NotAllowWhitespace => {
                 !text.chars().any(|c| c.is_whitespace())
             },
+            FormatChecker::Json => {
+                serde_json::from_str::<serde_json::Value>(text).is_ok()
+            },
+            FormatChecker::Regex => Regex::new(text).is_ok(),
+            FormatChecker::Number => text.parse::<u64>().is_ok(),
             FormatChecker::None => true
         }
     }
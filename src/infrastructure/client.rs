@@ -1,49 +1,130 @@
 pub mod common;
 pub mod telegram;
+pub mod slack;
+pub mod discord;
+pub mod matrix;
+pub mod webhook;
+pub mod email;
+pub mod console;
 
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use derive_new::new;
+use log::debug;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::Receiver;
 pub use common::*;
 use crate::application::client::{ClientLoader, MessageGateway};
 use crate::application::worker::Worker;
-use crate::domain::client::Message;
+use crate::domain::client::{Message, Reply};
 use crate::infrastructure::{client};
 use crate::application::worker::WorkerRunner;
 use crate::domain::config::Config;
 use crate::domain::file_accessor::FileAccessor;
 
+const MESSAGE_LIMIT: usize = 4000;
+const DEFAULT_ATTACHMENT_THRESHOLD: usize = MESSAGE_LIMIT;
+
 #[derive(new, Clone)]
 pub struct MessageAdapter {
-    client_loader: Arc<dyn ClientLoader>
+    client_loader: Arc<dyn ClientLoader>,
+    #[new(value = "DEFAULT_ATTACHMENT_THRESHOLD")]
+    attachment_threshold: usize
 }
 
 #[async_trait]
 impl MessageGateway for MessageAdapter {
-    async fn send_message(&self, client_name: &str, chat_id: &str, message: &str) {
+    async fn send_message(&self, client_name: &str, chat_id: &str, message: Reply) {
         let client = self.client_loader.find(client_name)
             .expect(format!("client({client_name}) is not available").as_str());
 
-        let total_len = message.len();
-        
-        let mut cut_length = 0;
-        
-        while cut_length < total_len {
-            let end = std::cmp::min(cut_length + 4000, total_len);
-            let chunk = &message[cut_length..end];
-            
-            client.send_message(chat_id, chunk).await;
-            
-            cut_length = end;
+        if let Some(code) = &message.code
+            && code.len() > self.attachment_threshold {
+            let caption = Reply { code: None, ..message.clone() };
+            if client.send_file(chat_id, Self::file_name(&message).as_str(), code.as_bytes(), &caption).await {
+                return;
+            }
+            debug!("attachment is not sent by {client_name}, falling back to messages");
+        }
+
+        for chunk in MessageAdapter::split(message) {
+            client.send_reply(chat_id, &chunk).await;
             tokio::time::sleep(std::time::Duration::from_millis(500)).await;
         }
     }
 }
 
+impl MessageAdapter {
+    pub fn with_attachment_threshold(mut self, attachment_threshold: usize) -> Self {
+        self.attachment_threshold = attachment_threshold;
+        self
+    }
+
+    /// Names the attachment after the title, e.g. `logs-main-last-100-lines.log`.
+    fn file_name(message: &Reply) -> String {
+        let title = message.title.as_deref().unwrap_or("output").to_lowercase();
+        let name = title
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        if name.is_empty() {
+            return String::from("output.log");
+        }
+        format!("{name}.log")
+    }
+
+    /// Splits a reply too long for one message into a header followed by text and code chunks,
+    /// keeping the buttons below the last chunk.
+    fn split(message: Reply) -> Vec<Reply> {
+        if message.to_plain_text().len() <= MESSAGE_LIMIT {
+            return if message.is_empty() { Vec::new() } else { vec![message] };
+        }
+
+        let mut replies = vec![Reply {
+            severity: message.severity,
+            title: message.title,
+            fields: message.fields,
+            ..Default::default()
+        }];
+        replies.extend(Self::chunks(message.text.as_str()).into_iter().map(Reply::from));
+        if let Some(code) = &message.code {
+            replies.extend(Self::chunks(code.as_str()).into_iter().map(|chunk| Reply::default().with_code(chunk)));
+        }
+        replies.retain(|reply| !reply.is_empty());
+
+        if let Some(last) = replies.last_mut() {
+            last.buttons = message.buttons;
+        }
+        replies
+    }
+
+    /// Cuts text into chunks of at most `MESSAGE_LIMIT` bytes, preferring line breaks
+    /// and never cutting inside a UTF-8 character.
+    fn chunks(text: &str) -> Vec<&str> {
+        let mut chunks = Vec::new();
+        let mut rest = text;
+        while rest.len() > MESSAGE_LIMIT {
+            let mut end = MESSAGE_LIMIT;
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            if let Some(line_end) = rest[..end].rfind('\n') {
+                end = line_end + 1;
+            }
+            let (chunk, remaining) = rest.split_at(end);
+            chunks.push(chunk);
+            rest = remaining;
+        }
+        if !rest.is_empty() {
+            chunks.push(rest);
+        }
+        chunks
+    }
+}
+
 #[derive(Clone, new)]
 pub struct ClientManager {
     worker_runner: Arc<Mutex<WorkerRunner>>,
@@ -98,3 +179,57 @@ impl ClientLoader for ClientManager {
         rx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::client::telegram::TelegramClient;
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+    use crate::infrastructure::common::stub_server::StubServer;
+
+    #[test]
+    fn chunks_keep_utf8_characters_whole() {
+        let text = format!("a{}", "가".repeat(2000));
+        let chunks = MessageAdapter::chunks(text.as_str());
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= MESSAGE_LIMIT));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn chunks_prefer_line_breaks() {
+        let line = format!("{}\n", "a".repeat(99));
+        let text = line.repeat(50);
+        let chunks = MessageAdapter::chunks(text.as_str());
+
+        assert_eq!(chunks[0].len(), 4000);
+        assert!(chunks[0].ends_with('\n'));
+        assert_eq!(chunks[1], line.repeat(10));
+    }
+
+    #[tokio::test]
+    async fn send_message_attaches_long_code() {
+        let server = StubServer::start(vec![
+            ("/bottoken/sendDocument", String::from(r#"{"ok":true,"result":{}}"#))
+        ]).await;
+        let telegram = TelegramClient::with_base_url("telegram".to_string(), "token".to_string(), server.base_url.clone());
+        let clients: HashMap<String, Box<dyn Client>> = HashMap::from([(String::from("telegram"), Box::new(telegram) as Box<dyn Client>)]);
+        let client_manager = ClientManager::new(
+            Arc::new(Mutex::new(WorkerRunner::new())),
+            Arc::new(Mutex::new(clients)),
+            Arc::new(MemoryFileAccessor::new(Config::new(None)))
+        );
+        let message_adapter = MessageAdapter::new(Arc::new(client_manager)).with_attachment_threshold(10);
+
+        let reply = Reply::titled("Logs: main (last 2 lines)").with_code("line 1\nline 2");
+        message_adapter.send_message("telegram", "4", reply).await;
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].header("content-type").unwrap().starts_with("multipart/form-data"));
+        assert!(requests[0].body.contains(r#"filename="logs-main-last-2-lines.log""#));
+        assert!(requests[0].body.contains("line 1\nline 2"));
+        assert!(requests[0].body.contains("<b>Logs: main (last 2 lines)</b>"));
+    }
+}
@@ -1,15 +1,35 @@
 
+use std::sync::Arc;
 use dyn_clone::{clone_trait_object, DynClone};
 use async_trait::async_trait;
+use log::error;
 use tokio::sync::mpsc::Sender;
 use crate::application::worker::Worker;
-use crate::domain::client::Message;
+use crate::domain::client::{Message, Reply};
 use crate::domain::config::ClientConfig;
+use crate::infrastructure::client::console::ConsoleClient;
+use crate::infrastructure::client::discord::DiscordClient;
+use crate::infrastructure::client::email::EmailClient;
+use crate::infrastructure::client::matrix::MatrixClient;
+use crate::infrastructure::client::slack::SlackClient;
 use crate::infrastructure::client::telegram::TelegramClient;
+use crate::infrastructure::client::webhook::WebhookClient;
+use crate::infrastructure::common::file_accessor::get_matrix_sync_file_accessor;
 
 #[async_trait]
 pub trait Client : Worker + DynClone + Send + Sync {
     async fn send_message(&self, chat_id: &str, data: &str) -> bool;
+
+    /// Sends a reply rendered natively. Clients without formatting send it as plain text.
+    async fn send_reply(&self, chat_id: &str, reply: &Reply) -> bool {
+        self.send_message(chat_id, reply.to_plain_text().as_str()).await
+    }
+
+    /// Uploads `data` as a file with the reply as its caption.
+    /// Returns false if it is not sent (or not supported), so the caller can fall back to messages.
+    async fn send_file(&self, _chat_id: &str, _file_name: &str, _data: &[u8], _caption: &Reply) -> bool {
+        false
+    }
     fn subscribe(&mut self, tx: Sender<Message>);
 }
 
@@ -17,7 +37,52 @@ pub fn from(config: ClientConfig) -> Option<Box<dyn Client>> {
     match config.kind.as_str() {
         "telegram" => {
             let token = config.token?;
-            Some(Box::new(TelegramClient::new(config.name, token)))
+            let mut client = match config.base_url {
+                Some(base_url) => TelegramClient::with_base_url(config.name, token, base_url),
+                None => TelegramClient::new(config.name, token)
+            };
+            if let Some(webhook) = config.webhook {
+                client = client.with_webhook(webhook);
+            }
+            Some(Box::new(client))
+        },
+        "slack" => {
+            let token = config.token?;
+            let app_token = config.app_token?;
+            Some(Box::new(SlackClient::new(config.name, token, app_token, config.base_url)))
+        },
+        "discord" => {
+            let token = config.token?;
+            Some(Box::new(DiscordClient::new(config.name, token, config.allowed_channels, config.base_url)))
+        },
+        "matrix" => {
+            let token = config.token?;
+            let homeserver_url = config.base_url?;
+            let sync_file_accessor = Arc::new(get_matrix_sync_file_accessor(config.name.as_str()));
+            Some(Box::new(MatrixClient::new(config.name, homeserver_url, token, sync_file_accessor)))
+        },
+        "webhook" => {
+            let url = config.url?;
+            match WebhookClient::new(config.name, url, config.headers, config.body_template) {
+                Ok(client) => Some(Box::new(client)),
+                Err(e) => {
+                    error!("[WebhookClient] Invalid body template: {e}");
+                    None
+                }
+            }
+        },
+        "email" => {
+            let smtp = config.smtp?;
+            match EmailClient::new(config.name, smtp) {
+                Ok(client) => Some(Box::new(client)),
+                Err(e) => {
+                    error!("[EmailClient] Invalid smtp config: {e}");
+                    None
+                }
+            }
+        },
+        "console" => {
+            Some(Box::new(ConsoleClient::new(config.name, config.socket_path)))
         }
         _ => None,
     }
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, error, trace, warn};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::Sender;
+use crate::application::worker::Worker;
+use crate::domain;
+use crate::infrastructure::client::common::Client;
+
+pub const STDIN_CHAT_ID: &str = "stdin";
+
+type Input = Lines<BufReader<Box<dyn AsyncRead + Send + Unpin>>>;
+type Output = Box<dyn AsyncWrite + Send + Unpin>;
+
+#[derive(Clone)]
+enum Source {
+    Stream(Arc<Mutex<Input>>),
+    Socket(String)
+}
+
+/// Local client which reads commands line by line from stdin (or a unix socket)
+/// and writes responses back, for offline operation and testing.
+#[derive(Clone)]
+pub struct ConsoleClient {
+    name: String,
+    source: Source,
+    outputs: Arc<Mutex<HashMap<String, Output>>>,
+    tx: Option<Sender<domain::client::Message>>
+}
+
+impl ConsoleClient {
+    pub fn new(name: String, socket_path: Option<String>) -> Self {
+        trace!("ConsoleClient::new(name: {}, socket_path: {:?})", &name, &socket_path);
+        match socket_path {
+            Some(socket_path) => Self {
+                name,
+                source: Source::Socket(socket_path),
+                outputs: Arc::new(Mutex::new(HashMap::new())),
+                tx: None
+            },
+            None => Self::with_io(name, Box::new(tokio::io::stdin()), Box::new(tokio::io::stdout()))
+        }
+    }
+
+    pub fn with_io(name: String, input: Box<dyn AsyncRead + Send + Unpin>, output: Output) -> Self {
+        let outputs = HashMap::from([(String::from(STDIN_CHAT_ID), output)]);
+        Self {
+            name,
+            source: Source::Stream(Arc::new(Mutex::new(BufReader::new(input).lines()))),
+            outputs: Arc::new(Mutex::new(outputs)),
+            tx: None
+        }
+    }
+
+    async fn forward(name: &str, tx: &Option<Sender<domain::client::Message>>, chat_id: &str, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+
+        let message = domain::client::Message::new(
+            name.to_string(), chat_id.to_string(), line.to_string()
+        );
+        debug!("created message: {:?}", &message);
+
+        if let Some(tx) = tx
+            && let Err(e) = tx.send(message).await {
+            warn!("[ConsoleClient] Err: {}", e);
+        }
+    }
+
+    async fn read_stream(&self, input: &Mutex<Input>) -> Result<()> {
+        let mut input = input.lock().await;
+        while let Some(line) = input.next_line().await? {
+            Self::forward(self.name.as_str(), &self.tx, STDIN_CHAT_ID, line.as_str()).await;
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    async fn listen_socket(&self, socket_path: &str) -> Result<()> {
+        use tokio::net::UnixListener;
+
+        // A stale socket file from a previous run would make bind fail
+        let _ = tokio::fs::remove_file(socket_path).await;
+        let listener = UnixListener::bind(socket_path)?;
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            // Connections of the same user share one chat, so registration survives reconnects
+            let chat_id = match stream.peer_cred() {
+                Ok(cred) => format!("uid:{}", cred.uid()),
+                Err(_) => String::from("unknown")
+            };
+            debug!("console connection from {}", &chat_id);
+
+            let (reader, writer) = stream.into_split();
+            self.outputs.lock().await.insert(chat_id.clone(), Box::new(writer));
+
+            let name = self.name.clone();
+            let tx = self.tx.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    Self::forward(name.as_str(), &tx, chat_id.as_str(), line.as_str()).await;
+                }
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn listen_socket(&self, _socket_path: &str) -> Result<()> {
+        Err(anyhow::anyhow!("Unix sockets are not supported on this platform"))
+    }
+}
+
+#[async_trait]
+impl Client for ConsoleClient {
+
+    async fn send_message(&self, chat_id: &str, data: &str) -> bool {
+        trace!("Client::send_message(chat_id: {}, data: ...)", chat_id);
+        let mut outputs = self.outputs.lock().await;
+        let output = match outputs.get_mut(chat_id) {
+            Some(output) => output,
+            None => {
+                warn!("[ConsoleClient] No connection for chat: {}", chat_id);
+                return false;
+            }
+        };
+
+        let result: Result<()> = async {
+            output.write_all(data.as_bytes()).await?;
+            output.write_all(b"\n").await?;
+            output.flush().await?;
+            Ok(())
+        }.await;
+
+        if let Err(e) = result {
+            error!("[ConsoleClient] Err: {e}");
+            outputs.remove(chat_id);
+            return false;
+        }
+        true
+    }
+
+    fn subscribe(&mut self, tx: Sender<domain::client::Message>) {
+        trace!("Client::subscribe");
+        self.tx = Some(tx);
+    }
+}
+
+#[async_trait]
+impl Worker for ConsoleClient {
+    async fn on_tick(&mut self) -> bool {
+        trace!("Worker::on_tick for {}", &self.name);
+        match self.source.clone() {
+            Source::Stream(input) => {
+                // Stop once the input is closed
+                if let Err(e) = self.read_stream(&input).await {
+                    error!("[ConsoleClient] Err: {e}");
+                }
+                false
+            },
+            Source::Socket(socket_path) => {
+                if let Err(e) = self.listen_socket(socket_path.as_str()).await {
+                    error!("[ConsoleClient] Err: {socket_path}: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+                true
+            }
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn interval(&self) -> i32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{duplex, AsyncReadExt};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn on_tick_reads_lines_until_eof() {
+        let (output, mut written) = duplex(1024);
+        let mut client = ConsoleClient::with_io(
+            "console".to_string(),
+            Box::new(&b"/health\n\n  /logs main 10  \n"[..]),
+            Box::new(output)
+        );
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        assert!(!client.on_tick().await);
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.client_name, "console");
+        assert_eq!(message.chat_id, STDIN_CHAT_ID);
+        assert_eq!(message.data, "/health");
+        assert_eq!(rx.try_recv().unwrap().data, "/logs main 10");
+        assert!(rx.try_recv().is_err());
+
+        assert!(client.send_message(STDIN_CHAT_ID, "Healthy").await);
+        assert!(!client.send_message("unknown", "Healthy").await);
+        let mut buffer = [0u8; 8];
+        written.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"Healthy\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn on_tick_serves_unix_socket() {
+        use tokio::net::UnixStream;
+
+        let socket_path = std::env::temp_dir().join(format!("sw-console-{}.sock", uuid::Uuid::new_v4()));
+        let socket_path = socket_path.to_str().unwrap().to_string();
+        let mut client = ConsoleClient::new("console".to_string(), Some(socket_path.clone()));
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        let sender = client.clone();
+        tokio::spawn(async move { client.on_tick().await });
+
+        let mut stream = loop {
+            match UnixStream::connect(socket_path.as_str()).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await
+            }
+        };
+        stream.write_all(b"/health\n").await.unwrap();
+
+        let message = rx.recv().await.unwrap();
+        assert_eq!(message.data, "/health");
+        assert!(message.chat_id.starts_with("uid:"));
+
+        assert!(sender.send_message(message.chat_id.as_str(), "Healthy").await);
+        let mut buffer = [0u8; 8];
+        stream.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"Healthy\n");
+
+        let _ = std::fs::remove_file(socket_path);
+    }
+}
@@ -0,0 +1,281 @@
+pub mod dto;
+
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, error, trace, warn};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use crate::application::worker::Worker;
+use crate::domain;
+use crate::infrastructure::client::common::Client;
+use crate::infrastructure::client::discord::dto::{CreateMessageDto, GatewayBotResponse, GatewayPayload, Hello, Identify, MessageCreate, OP_DISPATCH, OP_HEARTBEAT, OP_HELLO, OP_IDENTIFY, OP_INVALID_SESSION, OP_RECONNECT};
+use crate::infrastructure::common::api_client::ApiClient;
+
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+const MESSAGE_LIMIT: usize = 2000;
+
+#[derive(Clone)]
+pub struct DiscordClient {
+    name: String,
+    token: String,
+    allowed_channels: Option<Vec<String>>,
+    api_client: Arc<ApiClient>,
+    tx: Option<Sender<domain::client::Message>>
+}
+
+impl DiscordClient {
+    pub fn new(name: String, token: String, allowed_channels: Option<Vec<String>>, base_url: Option<String>) -> Self {
+        trace!("DiscordClient::new(name: {}, token: ...)", &name);
+        Self {
+            name,
+            token,
+            allowed_channels,
+            api_client: Arc::new(ApiClient::new(base_url.unwrap_or(String::from(DISCORD_API_URL)))),
+            tx: None
+        }
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bot {}", self.token)
+    }
+
+    fn is_allowed(&self, channel_id: &str) -> bool {
+        match &self.allowed_channels {
+            Some(channels) => channels.iter().any(|channel| channel == channel_id),
+            None => true
+        }
+    }
+
+    async fn gateway_url(&self) -> Result<String> {
+        let authorization = self.authorization();
+        let response = self.api_client
+            .get_json::<GatewayBotResponse>("gateway/bot", Some(&[("Authorization", authorization.as_str())]), None)
+            .await
+            .map_err(|e| anyhow!("[DiscordClient] Err: {}", e))?;
+        Ok(format!("{}/?v=10&encoding=json", response.url.trim_end_matches('/')))
+    }
+
+    async fn listen(&self) -> Result<()> {
+        let url = self.gateway_url().await?;
+        debug!("gateway url: {}", &url);
+        let (mut socket, _) = connect_async(url.as_str()).await?;
+
+        let hello = match socket.next().await {
+            Some(Ok(WsMessage::Text(text))) => serde_json::from_str::<GatewayPayload>(text.as_str())?,
+            other => return Err(anyhow!("[DiscordClient] Expected hello, got {:?}", other))
+        };
+        if hello.op != OP_HELLO {
+            return Err(anyhow!("[DiscordClient] Expected hello, got op {}", hello.op));
+        }
+        let hello: Hello = serde_json::from_value(hello.d)?;
+
+        let identify = GatewayPayload::new(OP_IDENTIFY, serde_json::to_value(Identify::new(self.token.as_str()))?);
+        socket.send(WsMessage::text(serde_json::to_string(&identify)?)).await?;
+
+        let mut heartbeat = tokio::time::interval(Duration::from_millis(hello.heartbeat_interval));
+        heartbeat.tick().await;
+        let mut sequence: Option<u64> = None;
+
+        loop {
+            let frame = tokio::select! {
+                _ = heartbeat.tick() => {
+                    let payload = GatewayPayload::new(OP_HEARTBEAT, json!(sequence));
+                    socket.send(WsMessage::text(serde_json::to_string(&payload)?)).await?;
+                    continue;
+                },
+                frame = socket.next() => frame
+            };
+
+            let text = match frame {
+                Some(Ok(WsMessage::Text(text))) => text,
+                Some(Ok(WsMessage::Close(frame))) => {
+                    debug!("gateway closed: {:?}", frame);
+                    break;
+                },
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => break
+            };
+
+            let payload: GatewayPayload = match serde_json::from_str(text.as_str()) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("[DiscordClient] Fail to parse payload: {e}");
+                    continue;
+                }
+            };
+            if payload.s.is_some() {
+                sequence = payload.s;
+            }
+
+            match payload.op {
+                OP_DISPATCH if payload.t.as_deref() == Some("MESSAGE_CREATE") => {
+                    self.handle_message(payload.d).await;
+                },
+                OP_HEARTBEAT => {
+                    let payload = GatewayPayload::new(OP_HEARTBEAT, json!(sequence));
+                    socket.send(WsMessage::text(serde_json::to_string(&payload)?)).await?;
+                },
+                OP_RECONNECT | OP_INVALID_SESSION => {
+                    debug!("gateway requested reconnect (op {})", payload.op);
+                    break;
+                },
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_message(&self, data: Value) {
+        let message: MessageCreate = match serde_json::from_value(data) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("[DiscordClient] Fail to parse message: {e}");
+                return;
+            }
+        };
+
+        if message.author.bot || !self.is_allowed(message.channel_id.as_str()) {
+            return;
+        }
+
+        let message = domain::client::Message::new(
+            self.get_name().to_string(), message.channel_id, message.content
+        );
+        debug!("created message: {:?}", &message);
+
+        if let Some(tx) = &self.tx
+            && let Err(e) = tx.send(message).await {
+            warn!("[DiscordClient] Err: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl Client for DiscordClient {
+
+    async fn send_message(&self, chat_id: &str, data: &str) -> bool {
+        trace!("Client::send_message(chat_id: {}, data: ...)", chat_id);
+        let authorization = self.authorization();
+        let path = format!("channels/{chat_id}/messages");
+
+        // Discord rejects messages over 2000 characters
+        let chars: Vec<char> = data.chars().collect();
+        for chunk in chars.chunks(MESSAGE_LIMIT) {
+            let content: String = chunk.iter().collect();
+            let response = self.api_client
+                .post_json::<CreateMessageDto, Value>(
+                    path.as_str(),
+                    &CreateMessageDto::new(content.as_str()),
+                    Some(&[("Authorization", authorization.as_str())]),
+                    None).await;
+
+            if let Err(e) = response {
+                error!("[DiscordClient] Err: {e}");
+                return false;
+            }
+        }
+        true
+    }
+
+    fn subscribe(&mut self, tx: Sender<domain::client::Message>) {
+        trace!("Client::subscribe");
+        self.tx = Some(tx);
+    }
+}
+
+#[async_trait]
+impl Worker for DiscordClient {
+    async fn on_tick(&mut self) -> bool {
+        trace!("Worker::on_tick for {}", &self.name);
+        // One tick lasts for the lifetime of a gateway session
+        if let Err(e) = self.listen().await {
+            error!("[DiscordClient] Err: {e}");
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+        true
+    }
+
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn interval(&self) -> i32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::accept_async;
+    use crate::infrastructure::common::stub_server::StubServer;
+
+    #[tokio::test]
+    async fn send_message() {
+        let server = StubServer::start(vec![
+            ("/channels/42/messages", String::from(r#"{"id":"1"}"#))
+        ]).await;
+        let client = DiscordClient::new("discord".to_string(), "secret".to_string(), None, Some(server.base_url.clone()));
+
+        assert!(client.send_message("42", "x".repeat(2500).as_str()).await);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("authorization"), Some("Bot secret"));
+        let body: Value = serde_json::from_str(requests[1].body.as_str()).unwrap();
+        assert_eq!(body, json!({"content": "x".repeat(500)}));
+    }
+
+    #[tokio::test]
+    async fn on_tick_receives_gateway_messages() {
+        let gateway_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway_url = format!("ws://{}", gateway_listener.local_addr().unwrap());
+        let server = StubServer::start(vec![
+            ("/gateway/bot", json!({"url": gateway_url}).to_string())
+        ]).await;
+
+        let gateway_task = tokio::spawn(async move {
+            let (stream, _) = gateway_listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            socket.send(WsMessage::text(json!({"op": 10, "d": {"heartbeat_interval": 60000}}).to_string())).await.unwrap();
+
+            let identify = match socket.next().await {
+                Some(Ok(WsMessage::Text(text))) => serde_json::from_str::<Value>(text.as_str()).unwrap(),
+                other => panic!("unexpected frame: {:?}", other)
+            };
+
+            for (channel_id, bot) in [("100", false), ("200", false), ("100", true)] {
+                socket.send(WsMessage::text(json!({
+                    "op": 0, "s": 1, "t": "MESSAGE_CREATE",
+                    "d": {"channel_id": channel_id, "content": "/health", "author": {"id": "7", "bot": bot}}
+                }).to_string())).await.unwrap();
+            }
+            socket.send(WsMessage::text(json!({"op": 7, "d": null}).to_string())).await.unwrap();
+            identify
+        });
+
+        let mut client = DiscordClient::new("discord".to_string(), "secret".to_string(), Some(vec![String::from("100")]), Some(server.base_url.clone()));
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        assert!(client.on_tick().await);
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.client_name, "discord");
+        assert_eq!(message.chat_id, "100");
+        assert_eq!(message.data, "/health");
+        assert!(rx.try_recv().is_err());
+
+        let identify = gateway_task.await.unwrap();
+        assert_eq!(identify["op"], 2);
+        assert_eq!(identify["d"]["token"], "secret");
+    }
+}
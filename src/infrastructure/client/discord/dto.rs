@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const OP_DISPATCH: u8 = 0;
+pub const OP_HEARTBEAT: u8 = 1;
+pub const OP_IDENTIFY: u8 = 2;
+pub const OP_RECONNECT: u8 = 7;
+pub const OP_INVALID_SESSION: u8 = 9;
+pub const OP_HELLO: u8 = 10;
+
+// GUILD_MESSAGES | DIRECT_MESSAGES | MESSAGE_CONTENT
+pub const INTENTS: u64 = (1 << 9) | (1 << 12) | (1 << 15);
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateMessageDto {
+    content: String
+}
+
+impl CreateMessageDto {
+    pub fn new(content: &str) -> Self {
+        Self {
+            content: content.to_string()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GatewayBotResponse {
+    pub url: String
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GatewayPayload {
+    pub op: u8,
+    #[serde(default)]
+    pub d: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<String>
+}
+
+impl GatewayPayload {
+    pub fn new(op: u8, d: Value) -> Self {
+        Self {
+            op,
+            d,
+            s: None,
+            t: None
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Hello {
+    pub heartbeat_interval: u64
+}
+
+#[derive(Serialize, Debug)]
+pub struct Identify {
+    token: String,
+    intents: u64,
+    properties: IdentifyProperties
+}
+
+#[derive(Serialize, Debug)]
+struct IdentifyProperties {
+    os: String,
+    browser: String,
+    device: String
+}
+
+impl Identify {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            intents: INTENTS,
+            properties: IdentifyProperties {
+                os: std::env::consts::OS.to_string(),
+                browser: String::from("server-watchdog"),
+                device: String::from("server-watchdog")
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MessageCreate {
+    pub channel_id: String,
+    pub content: String,
+    pub author: Author
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Author {
+    pub id: String,
+    #[serde(default)]
+    pub bot: bool
+}
@@ -0,0 +1,211 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use log::{debug, error, trace};
+use tokio::sync::mpsc::Sender;
+use crate::application::worker::Worker;
+use crate::domain;
+use crate::domain::config::{SmtpConfig, SmtpSecurity};
+use crate::infrastructure::client::common::Client;
+
+const SUBJECT_PREFIX: &str = "[server-watchdog]";
+const SUBJECT_LENGTH: usize = 80;
+
+/// Outbound only client which mails every message to the chat identity (recipient address).
+#[derive(Clone)]
+pub struct EmailClient {
+    name: String,
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>
+}
+
+impl EmailClient {
+    pub fn new(name: String, smtp: SmtpConfig) -> Result<Self> {
+        trace!("EmailClient::new(name: {}, host: {}, port: {})", &name, &smtp.host, smtp.port);
+        let mut builder = match smtp.security() {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(smtp.host.as_str())?,
+            SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp.host.as_str())?,
+            SmtpSecurity::Plaintext => {
+                // Credentials would be sent in cleartext
+                if smtp.username.is_some() || smtp.password.is_some() {
+                    bail!("SMTP credentials need tls or starttls security");
+                }
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(smtp.host.as_str())
+            }
+        };
+        builder = builder.port(smtp.port);
+
+        if let (Some(username), Some(password)) = (smtp.username, smtp.password) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self {
+            name,
+            from: smtp.from.parse()?,
+            transport: builder.build()
+        })
+    }
+
+    fn subject(data: &str) -> String {
+        let first_line = data.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+        let mut subject: String = first_line.chars().take(SUBJECT_LENGTH).collect();
+        if first_line.chars().count() > SUBJECT_LENGTH {
+            subject.push_str("...");
+        }
+        format!("{SUBJECT_PREFIX} {subject}")
+    }
+
+    async fn send(&self, recipient: &str, data: &str) -> Result<()> {
+        let email = lettre::Message::builder()
+            .from(self.from.clone())
+            .to(recipient.parse()?)
+            .subject(Self::subject(data))
+            .header(ContentType::TEXT_PLAIN)
+            .body(data.to_string())?;
+
+        let response = self.transport.send(email).await?;
+        debug!("smtp response: {:?}", response);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Client for EmailClient {
+
+    async fn send_message(&self, chat_id: &str, data: &str) -> bool {
+        trace!("Client::send_message(chat_id: {}, data: ...)", chat_id);
+        match self.send(chat_id, data).await {
+            Ok(_) => true,
+            Err(e) => {
+                error!("[EmailClient] Err: {e}");
+                false
+            }
+        }
+    }
+
+    fn subscribe(&mut self, _tx: Sender<domain::client::Message>) {
+        trace!("Client::subscribe");
+    }
+}
+
+#[async_trait]
+impl Worker for EmailClient {
+    async fn on_tick(&mut self) -> bool {
+        // Nothing to receive, stop ticking
+        false
+    }
+
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn interval(&self) -> i32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Accepts a single smtp session and returns every line the client sent.
+    async fn start_smtp_sink() -> (u16, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut received = Vec::new();
+            let mut in_data = false;
+
+            writer.write_all(b"220 localhost ESMTP sink\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                received.push(line.clone());
+                let reply: &[u8] = if in_data {
+                    if line != "." {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-localhost\r\n250 AUTH PLAIN LOGIN\r\n"
+                } else if line.starts_with("AUTH") {
+                    b"235 authenticated\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+            received
+        });
+        (port, handle)
+    }
+
+    fn smtp_config(port: u16) -> SmtpConfig {
+        SmtpConfig {
+            host: String::from("127.0.0.1"),
+            port,
+            security: Some(SmtpSecurity::Plaintext),
+            starttls: false,
+            username: None,
+            password: None,
+            from: String::from("Watchdog <watchdog@example.org>")
+        }
+    }
+
+    #[tokio::test]
+    async fn send_message() {
+        let (port, sink) = start_smtp_sink().await;
+        let client = EmailClient::new("email".to_string(), smtp_config(port)).unwrap();
+
+        assert!(client.send_message("ops@example.org", "Keyword 'ERROR' found in logs of server 'main'\nLog: ERROR boom").await);
+
+        let received = sink.await.unwrap();
+        assert!(!received.iter().any(|line| line.starts_with("AUTH")));
+        assert!(received.contains(&String::from("MAIL FROM:<watchdog@example.org>")));
+        assert!(received.contains(&String::from("RCPT TO:<ops@example.org>")));
+        assert!(received.contains(&String::from("Subject: [server-watchdog] Keyword 'ERROR' found in logs of server 'main'")));
+        assert!(received.contains(&String::from("Log: ERROR boom")));
+    }
+
+    #[test]
+    fn plaintext_rejects_credentials() {
+        let mut smtp = smtp_config(25);
+        smtp.username = Some(String::from("watchdog"));
+        smtp.password = Some(String::from("secret"));
+        assert!(EmailClient::new("email".to_string(), smtp.clone()).is_err());
+
+        // Configs from before `security` fall back to plaintext unless starttls was set
+        smtp.security = None;
+        assert!(EmailClient::new("email".to_string(), smtp.clone()).is_err());
+        smtp.starttls = true;
+        assert_eq!(smtp.security(), SmtpSecurity::Starttls);
+        assert!(EmailClient::new("email".to_string(), smtp).is_ok());
+    }
+
+    #[tokio::test]
+    async fn send_message_rejects_invalid_recipient() {
+        let client = EmailClient::new("email".to_string(), smtp_config(1)).unwrap();
+
+        assert!(!client.send_message("not an address", "hello").await);
+    }
+
+    #[test]
+    fn subject_is_truncated() {
+        let subject = EmailClient::subject(&format!("\n{}\nsecond", "a".repeat(100)));
+        assert_eq!(subject, format!("{SUBJECT_PREFIX} {}...", "a".repeat(SUBJECT_LENGTH)));
+    }
+}
@@ -0,0 +1,239 @@
+pub mod dto;
+
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::{debug, error, trace, warn};
+use serde_json::Value;
+use tokio::sync::mpsc::Sender;
+use uuid::Uuid;
+use crate::application::worker::Worker;
+use crate::domain;
+use crate::domain::file_accessor::FileAccessor;
+use crate::infrastructure::client::common::Client;
+use crate::infrastructure::client::matrix::dto::{SendMessageDto, SyncResponse, SyncState, WhoAmIResponse};
+use crate::infrastructure::common::api_client::ApiClient;
+
+const SYNC_TIMEOUT_MILLIS: &str = "30000";
+// {"room":{"timeline":{"limit":1}}}, keeps the first sync small since its events are discarded
+const INITIAL_SYNC_FILTER: &str = "%7B%22room%22%3A%7B%22timeline%22%3A%7B%22limit%22%3A1%7D%7D%7D";
+
+#[derive(Clone)]
+pub struct MatrixClient {
+    name: String,
+    api_client: Arc<ApiClient>,
+    user_id: Option<String>,
+    sync_file_accessor: Arc<dyn FileAccessor<SyncState>>,
+    tx: Option<Sender<domain::client::Message>>
+}
+
+impl MatrixClient {
+    pub fn new(name: String, homeserver_url: String, access_token: String, sync_file_accessor: Arc<dyn FileAccessor<SyncState>>) -> Self {
+        trace!("MatrixClient::new(name: {}, homeserver_url: {}, access_token: ...)", &name, &homeserver_url);
+        let base_url = format!("{}/_matrix/client/v3", homeserver_url.trim_end_matches('/'));
+        Self {
+            name,
+            api_client: Arc::new(ApiClient::new(base_url).with_token(access_token)),
+            user_id: None,
+            sync_file_accessor,
+            tx: None
+        }
+    }
+
+    async fn get_user_id(&mut self) -> Result<String> {
+        if self.user_id.is_none() {
+            let response = self.api_client
+                .get_json::<WhoAmIResponse>("account/whoami", None, None)
+                .await
+                .map_err(|e| anyhow!("[MatrixClient] Err: {}", e))?;
+            self.user_id = Some(response.user_id);
+        }
+        Ok(self.user_id.clone().unwrap())
+    }
+
+    async fn sync(&mut self) -> Result<Vec<(String, String)>> {
+        trace!("MatrixClient::sync");
+        let user_id = self.get_user_id().await?;
+        let mut state = self.sync_file_accessor.read().await
+            .map_err(|e| anyhow!("[MatrixClient] Fail to read sync state: {}", e))?;
+
+        // Without a stored token, only fetch the current position so old commands are not replayed
+        let response = match &state.next_batch {
+            Some(since) => {
+                self.api_client.get_json::<SyncResponse>(
+                    "sync", None, Some(&[("since", since.as_str()), ("timeout", SYNC_TIMEOUT_MILLIS)])
+                ).await
+            },
+            None => {
+                self.api_client.get_json::<SyncResponse>(
+                    "sync", None, Some(&[("filter", INITIAL_SYNC_FILTER), ("timeout", "0")])
+                ).await
+            }
+        }.map_err(|e| anyhow!("[MatrixClient] Err: {}", e))?;
+        debug!("sync response: {:?}", &response);
+
+        let messages = if state.next_batch.is_some() {
+            response.rooms.join.into_iter()
+                .flat_map(|(room_id, room)| {
+                    room.timeline.events.into_iter()
+                        .filter(|event| event.r#type == "m.room.message" && event.sender != user_id)
+                        .filter_map(|event| event.content.get("body").and_then(Value::as_str).map(String::from))
+                        .map(move |body| (room_id.clone(), body))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        state.next_batch = Some(response.next_batch);
+        self.sync_file_accessor.write(&state).await
+            .map_err(|e| anyhow!("[MatrixClient] Fail to write sync state: {}", e))?;
+
+        Ok(messages)
+    }
+}
+
+#[async_trait]
+impl Client for MatrixClient {
+
+    async fn send_message(&self, chat_id: &str, data: &str) -> bool {
+        trace!("Client::send_message(chat_id: {}, data: ...)", chat_id);
+        let path = format!("rooms/{}/send/m.room.message/{}", chat_id, Uuid::new_v4());
+        let response = self.api_client
+            .put_json::<SendMessageDto, Value>(path.as_str(), &SendMessageDto::new(data), None, None)
+            .await;
+
+        if let Err(e) = response {
+            error!("[MatrixClient] Err: {e}");
+            return false;
+        }
+        true
+    }
+
+    fn subscribe(&mut self, tx: Sender<domain::client::Message>) {
+        trace!("Client::subscribe");
+        self.tx = Some(tx);
+    }
+}
+
+#[async_trait]
+impl Worker for MatrixClient {
+    async fn on_tick(&mut self) -> bool {
+        trace!("Worker::on_tick for {}", &self.name);
+        let messages = match self.sync().await {
+            Ok(messages) => messages,
+            Err(e) => {
+                error!("[MatrixClient] Err: {e}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                return true;
+            }
+        };
+        debug!("{} messages received", messages.len());
+
+        for (room_id, body) in messages {
+            let message = domain::client::Message::new(
+                self.get_name().to_string(), room_id, body
+            );
+            debug!("created message: {:?}", &message);
+
+            if let Some(tx) = &self.tx
+                && let Err(e) = tx.send(message).await {
+                warn!("[MatrixClient] Err: {}", e);
+            }
+        }
+        true
+    }
+
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn interval(&self) -> i32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+    use crate::infrastructure::common::stub_server::StubServer;
+
+    fn sync_response() -> String {
+        json!({
+            "next_batch": "s2",
+            "rooms": {"join": {"!ops:example.org": {"timeline": {"events": [
+                {"type": "m.room.message", "sender": "@alice:example.org", "content": {"msgtype": "m.text", "body": "/health"}},
+                {"type": "m.room.message", "sender": "@watchdog:example.org", "content": {"msgtype": "m.text", "body": "Healthy"}},
+                {"type": "m.room.member", "sender": "@bob:example.org", "content": {"membership": "join"}}
+            ]}}}}
+        }).to_string()
+    }
+
+    async fn start_server() -> StubServer {
+        StubServer::start(vec![
+            ("/_matrix/client/v3/account/whoami", String::from(r#"{"user_id":"@watchdog:example.org"}"#)),
+            ("/_matrix/client/v3/sync", sync_response())
+        ]).await
+    }
+
+    #[tokio::test]
+    async fn on_tick_resumes_from_stored_token() {
+        let server = start_server().await;
+        let mut state = SyncState::new();
+        state.next_batch = Some(String::from("s1"));
+        let sync_file_accessor = Arc::new(MemoryFileAccessor::new(state));
+
+        let mut client = MatrixClient::new("matrix".to_string(), server.base_url.clone(), "secret".to_string(), sync_file_accessor.clone());
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        assert!(client.on_tick().await);
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.chat_id, "!ops:example.org");
+        assert_eq!(message.data, "/health");
+        assert!(rx.try_recv().is_err());
+
+        let sync_request = server.requests().into_iter()
+            .find(|request| request.path.starts_with("/_matrix/client/v3/sync"))
+            .unwrap();
+        assert!(sync_request.path.contains("since=s1"));
+        assert_eq!(sync_request.header("authorization"), Some("Bearer secret"));
+        assert_eq!(sync_file_accessor.read().await.unwrap().next_batch.as_deref(), Some("s2"));
+    }
+
+    #[tokio::test]
+    async fn on_tick_skips_history_without_token() {
+        let server = start_server().await;
+        let sync_file_accessor = Arc::new(MemoryFileAccessor::new(SyncState::new()));
+
+        let mut client = MatrixClient::new("matrix".to_string(), server.base_url.clone(), "secret".to_string(), sync_file_accessor.clone());
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        assert!(client.on_tick().await);
+
+        assert!(rx.try_recv().is_err());
+        assert_eq!(sync_file_accessor.read().await.unwrap().next_batch.as_deref(), Some("s2"));
+    }
+
+    #[tokio::test]
+    async fn send_message() {
+        let server = StubServer::start(vec![
+            ("/_matrix/client/v3/rooms/", String::from(r#"{"event_id":"$1"}"#))
+        ]).await;
+        let client = MatrixClient::new("matrix".to_string(), server.base_url.clone(), "secret".to_string(), Arc::new(MemoryFileAccessor::new(SyncState::new())));
+
+        assert!(client.send_message("!ops:example.org", "hello").await);
+
+        let request = &server.requests()[0];
+        assert_eq!(request.method, "PUT");
+        assert!(request.path.starts_with("/_matrix/client/v3/rooms/!ops:example.org/send/m.room.message/"));
+        let body: Value = serde_json::from_str(request.body.as_str()).unwrap();
+        assert_eq!(body, json!({"msgtype": "m.text", "body": "hello"}));
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Debug, new, Clone)]
+pub struct SyncState {
+    #[new(default)]
+    pub next_batch: Option<String>
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SendMessageDto {
+    msgtype: String,
+    body: String
+}
+
+impl SendMessageDto {
+    pub fn new(body: &str) -> Self {
+        Self {
+            msgtype: String::from("m.text"),
+            body: body.to_string()
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WhoAmIResponse {
+    pub user_id: String
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SyncResponse {
+    pub next_batch: String,
+    #[serde(default)]
+    pub rooms: Rooms
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Rooms {
+    #[serde(default)]
+    pub join: HashMap<String, JoinedRoom>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JoinedRoom {
+    #[serde(default)]
+    pub timeline: Timeline
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Timeline {
+    #[serde(default)]
+    pub events: Vec<RoomEvent>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RoomEvent {
+    pub r#type: String,
+    pub sender: String,
+    #[serde(default)]
+    pub content: Value
+}
@@ -0,0 +1,348 @@
+pub mod dto;
+pub mod format;
+
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, error, trace, warn};
+use serde_json::json;
+use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use crate::application::worker::Worker;
+use crate::domain;
+use crate::domain::client::Reply;
+use crate::infrastructure::client::common::Client;
+use crate::infrastructure::client::slack::dto::{AcknowledgeDto, CompleteUploadDto, ConnectionsOpenResponse, Envelope, EventPayload, PostMessageDto, SlackResponse, UploadUrlResponse};
+use crate::infrastructure::common::api_client::ApiClient;
+
+const SLACK_API_URL: &str = "https://slack.com/api";
+
+#[derive(Clone)]
+pub struct SlackClient {
+    name: String,
+    api_client: Arc<ApiClient>,
+    socket_api_client: Arc<ApiClient>,
+    tx: Option<Sender<domain::client::Message>>
+}
+
+impl SlackClient {
+    pub fn new(name: String, token: String, app_token: String, base_url: Option<String>) -> Self {
+        trace!("SlackClient::new(name: {}, token: ..., app_token: ...)", &name);
+        let base_url = base_url.unwrap_or(String::from(SLACK_API_URL));
+        Self {
+            name,
+            api_client: Arc::new(ApiClient::new(base_url.as_str()).with_token(token)),
+            socket_api_client: Arc::new(ApiClient::new(base_url).with_token(app_token)),
+            tx: None
+        }
+    }
+
+    async fn open_connection(&self) -> Result<String> {
+        trace!("SlackClient::open_connection");
+        let response = self.socket_api_client
+            .post_json::<_, ConnectionsOpenResponse>("apps.connections.open", &json!({}), None, None)
+            .await
+            .map_err(|e| anyhow!("[SlackClient] Err: {}", e))?;
+
+        if !response.ok {
+            return Err(anyhow!("[SlackClient] apps.connections.open: {}", response.error.unwrap_or_default()));
+        }
+        response.url.ok_or(anyhow!("[SlackClient] apps.connections.open returned no url"))
+    }
+
+    async fn listen(&self) -> Result<()> {
+        let url = self.open_connection().await?;
+        debug!("socket mode url: {}", &url);
+        let (mut socket, _) = connect_async(url.as_str()).await?;
+
+        while let Some(frame) = socket.next().await {
+            let text = match frame? {
+                WsMessage::Text(text) => text,
+                WsMessage::Close(_) => break,
+                _ => continue
+            };
+
+            let envelope: Envelope = match serde_json::from_str(text.as_str()) {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    warn!("[SlackClient] Fail to parse envelope: {e}");
+                    continue;
+                }
+            };
+            debug!("envelope received: {:?}", &envelope);
+
+            if let Some(envelope_id) = &envelope.envelope_id {
+                let ack = serde_json::to_string(&AcknowledgeDto::new(envelope_id))?;
+                socket.send(WsMessage::text(ack)).await?;
+            }
+
+            match envelope.r#type.as_str() {
+                "events_api" => self.handle_event(envelope.payload).await,
+                "disconnect" => {
+                    debug!("disconnect requested: {:?}", envelope.reason);
+                    break;
+                },
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_event(&self, payload: Option<EventPayload>) {
+        let event = match payload.and_then(|payload| payload.event) {
+            Some(event) => event,
+            None => return
+        };
+
+        // Skip edits, joins and our own (or other bots') messages
+        if event.r#type != "message" || event.subtype.is_some() || event.bot_id.is_some() {
+            return;
+        }
+
+        let (chat_id, data) = match (event.channel, event.text) {
+            (Some(channel), Some(text)) => (channel, text),
+            _ => return
+        };
+
+        let message = domain::client::Message::new(
+            self.get_name().to_string(), chat_id, data
+        );
+        debug!("created message: {:?}", &message);
+
+        if let Some(tx) = &self.tx
+            && let Err(e) = tx.send(message).await {
+            warn!("[SlackClient] Err: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl Client for SlackClient {
+
+    async fn send_message(&self, chat_id: &str, data: &str) -> bool {
+        trace!("Client::send_message(chat_id: {}, data: ...)", chat_id);
+        self.post_message(&PostMessageDto::new(chat_id, data)).await
+    }
+
+    async fn send_reply(&self, chat_id: &str, reply: &Reply) -> bool {
+        trace!("Client::send_reply(chat_id: {}, reply: ...)", chat_id);
+        // The plain text stays as the notification fallback
+        let dto = PostMessageDto::new(chat_id, reply.to_plain_text().as_str())
+            .with_blocks(format::to_blocks(reply));
+        self.post_message(&dto).await
+    }
+
+    async fn send_file(&self, chat_id: &str, file_name: &str, data: &[u8], caption: &Reply) -> bool {
+        trace!("Client::send_file(chat_id: {}, file_name: {}, data: {} bytes)", chat_id, file_name, data.len());
+        match self.upload_file(chat_id, file_name, data, caption).await {
+            Ok(_) => true,
+            Err(e) => {
+                error!("[SlackClient] Err: {e}");
+                false
+            }
+        }
+    }
+
+    fn subscribe(&mut self, tx: Sender<domain::client::Message>) {
+        trace!("Client::subscribe");
+        self.tx = Some(tx);
+    }
+}
+
+impl SlackClient {
+    /// Uploads through the external upload flow, which replaced `files.upload`.
+    async fn upload_file(&self, chat_id: &str, file_name: &str, data: &[u8], caption: &Reply) -> Result<()> {
+        let length = data.len().to_string();
+        let response = self.api_client
+            .post_form::<_, UploadUrlResponse>(
+                "files.getUploadURLExternal",
+                &[("filename", file_name), ("length", length.as_str())], None, None)
+            .await
+            .map_err(|e| anyhow!("{}", e))?;
+        if !response.ok {
+            return Err(anyhow!("files.getUploadURLExternal: {}", response.error.unwrap_or_default()));
+        }
+        let (upload_url, file_id) = match (response.upload_url, response.file_id) {
+            (Some(upload_url), Some(file_id)) => (upload_url, file_id),
+            _ => return Err(anyhow!("files.getUploadURLExternal returned no upload url"))
+        };
+
+        ApiClient::new(upload_url)
+            .post_bytes("", data.to_vec(), None, None)
+            .await
+            .map_err(|e| anyhow!("{}", e))?;
+
+        let initial_comment = (!caption.is_empty()).then(|| caption.to_plain_text());
+        let response = self.api_client
+            .post_json::<_, SlackResponse>(
+                "files.completeUploadExternal",
+                &CompleteUploadDto::new(file_id.as_str(), file_name, chat_id, initial_comment), None, None)
+            .await
+            .map_err(|e| anyhow!("{}", e))?;
+        if !response.ok {
+            return Err(anyhow!("files.completeUploadExternal: {}", response.error.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
+    async fn post_message(&self, dto: &PostMessageDto) -> bool {
+        let response = self.api_client
+            .post_json::<PostMessageDto, SlackResponse>("chat.postMessage", dto, None, None).await;
+
+        match response {
+            Ok(response) if response.ok => true,
+            Ok(response) => {
+                error!("[SlackClient] chat.postMessage: {}", response.error.unwrap_or_default());
+                false
+            },
+            Err(e) => {
+                error!("[SlackClient] Err: {e}");
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Worker for SlackClient {
+    async fn on_tick(&mut self) -> bool {
+        trace!("Worker::on_tick for {}", &self.name);
+        // One tick lasts for the lifetime of a socket mode connection
+        if let Err(e) = self.listen().await {
+            error!("[SlackClient] Err: {e}");
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+        true
+    }
+
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn interval(&self) -> i32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::accept_async;
+    use crate::infrastructure::common::stub_server::StubServer;
+
+    #[tokio::test]
+    async fn send_message() {
+        let server = StubServer::start(vec![
+            ("/chat.postMessage", String::from(r#"{"ok":true}"#))
+        ]).await;
+        let client = SlackClient::new("slack".to_string(), "xoxb-test".to_string(), "xapp-test".to_string(), Some(server.base_url.clone()));
+
+        assert!(client.send_message("C123", "hello").await);
+
+        let request = &server.requests()[0];
+        assert_eq!(request.header("authorization"), Some("Bearer xoxb-test"));
+        let body: serde_json::Value = serde_json::from_str(request.body.as_str()).unwrap();
+        assert_eq!(body, json!({"channel": "C123", "text": "hello"}));
+    }
+
+    #[tokio::test]
+    async fn send_reply_with_blocks() {
+        let server = StubServer::start(vec![
+            ("/chat.postMessage", String::from(r#"{"ok":true}"#))
+        ]).await;
+        let client = SlackClient::new("slack".to_string(), "xoxb-test".to_string(), "xapp-test".to_string(), Some(server.base_url.clone()));
+
+        assert!(client.send_reply("C123", &Reply::titled("Health").with_field("main", "Healthy")).await);
+
+        let body: serde_json::Value = serde_json::from_str(server.requests()[0].body.as_str()).unwrap();
+        assert_eq!(body["text"], "Health\nmain: Healthy");
+        assert_eq!(body["blocks"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn send_file_uploads_externally() {
+        let upload_server = StubServer::start(vec![("/upload", String::from("OK"))]).await;
+        let server = StubServer::start(vec![
+            ("/files.getUploadURLExternal", json!({"ok": true, "upload_url": format!("{}/upload/F1", upload_server.base_url), "file_id": "F1"}).to_string()),
+            ("/files.completeUploadExternal", String::from(r#"{"ok":true}"#))
+        ]).await;
+        let client = SlackClient::new("slack".to_string(), "xoxb-test".to_string(), "xapp-test".to_string(), Some(server.base_url.clone()));
+
+        assert!(client.send_file("C123", "main.log", b"line 1\nline 2", &Reply::titled("Logs: main")).await);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].body, "filename=main.log&length=13");
+        assert_eq!(upload_server.requests()[0].body, "line 1\nline 2");
+        let body: serde_json::Value = serde_json::from_str(requests[1].body.as_str()).unwrap();
+        assert_eq!(body, json!({
+            "files": [{"id": "F1", "title": "main.log"}],
+            "channel_id": "C123",
+            "initial_comment": "Logs: main"
+        }));
+    }
+
+    #[tokio::test]
+    async fn send_message_not_ok() {
+        let server = StubServer::start(vec![
+            ("/chat.postMessage", String::from(r#"{"ok":false,"error":"channel_not_found"}"#))
+        ]).await;
+        let client = SlackClient::new("slack".to_string(), "xoxb-test".to_string(), "xapp-test".to_string(), Some(server.base_url.clone()));
+
+        assert!(!client.send_message("C123", "hello").await);
+    }
+
+    #[tokio::test]
+    async fn on_tick_receives_socket_mode_events() {
+        let socket_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let socket_url = format!("ws://{}", socket_listener.local_addr().unwrap());
+        let server = StubServer::start(vec![
+            ("/apps.connections.open", json!({"ok": true, "url": socket_url}).to_string())
+        ]).await;
+
+        let socket_task = tokio::spawn(async move {
+            let (stream, _) = socket_listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            socket.send(WsMessage::text(r#"{"type":"hello"}"#)).await.unwrap();
+            socket.send(WsMessage::text(json!({
+                "type": "events_api",
+                "envelope_id": "env-1",
+                "payload": {"event": {"type": "message", "channel": "C123", "user": "U1", "text": "/health"}}
+            }).to_string())).await.unwrap();
+            socket.send(WsMessage::text(json!({
+                "type": "events_api",
+                "envelope_id": "env-2",
+                "payload": {"event": {"type": "message", "channel": "C123", "bot_id": "B1", "text": "echo"}}
+            }).to_string())).await.unwrap();
+
+            let mut acks = Vec::new();
+            while acks.len() < 2 {
+                if let Some(Ok(WsMessage::Text(text))) = socket.next().await {
+                    acks.push(text.to_string());
+                }
+            }
+            socket.send(WsMessage::text(r#"{"type":"disconnect","reason":"refresh_requested"}"#)).await.unwrap();
+            acks
+        });
+
+        let mut client = SlackClient::new("slack".to_string(), "xoxb-test".to_string(), "xapp-test".to_string(), Some(server.base_url.clone()));
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        assert!(client.on_tick().await);
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.client_name, "slack");
+        assert_eq!(message.chat_id, "C123");
+        assert_eq!(message.data, "/health");
+        assert!(rx.try_recv().is_err());
+
+        let acks = socket_task.await.unwrap();
+        assert_eq!(acks, vec![r#"{"envelope_id":"env-1"}"#, r#"{"envelope_id":"env-2"}"#]);
+        assert_eq!(server.requests()[0].header("authorization"), Some("Bearer xapp-test"));
+    }
+}
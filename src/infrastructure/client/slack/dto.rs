@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PostMessageDto {
+    channel: String,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocks: Option<Vec<serde_json::Value>>
+}
+
+impl PostMessageDto {
+    pub fn new(channel: &str, text: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            text: text.to_string(),
+            blocks: None
+        }
+    }
+
+    pub fn with_blocks(mut self, blocks: Vec<serde_json::Value>) -> Self {
+        self.blocks = Some(blocks);
+        self
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SlackResponse {
+    pub ok: bool,
+    pub error: Option<String>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UploadUrlResponse {
+    pub ok: bool,
+    pub upload_url: Option<String>,
+    pub file_id: Option<String>,
+    pub error: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompleteUploadDto {
+    files: Vec<UploadedFile>,
+    channel_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    initial_comment: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct UploadedFile {
+    id: String,
+    title: String
+}
+
+impl CompleteUploadDto {
+    pub fn new(file_id: &str, title: &str, channel_id: &str, initial_comment: Option<String>) -> Self {
+        Self {
+            files: vec![UploadedFile { id: file_id.to_string(), title: title.to_string() }],
+            channel_id: channel_id.to_string(),
+            initial_comment
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ConnectionsOpenResponse {
+    pub ok: bool,
+    pub url: Option<String>,
+    pub error: Option<String>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Envelope {
+    pub r#type: String,
+    pub envelope_id: Option<String>,
+    pub payload: Option<EventPayload>,
+    pub reason: Option<String>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EventPayload {
+    pub event: Option<Event>
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Event {
+    pub r#type: String,
+    pub subtype: Option<String>,
+    pub channel: Option<String>,
+    pub user: Option<String>,
+    pub bot_id: Option<String>,
+    pub text: Option<String>
+}
+
+#[derive(Serialize, Debug)]
+pub struct AcknowledgeDto {
+    envelope_id: String
+}
+
+impl AcknowledgeDto {
+    pub fn new(envelope_id: &str) -> Self {
+        Self {
+            envelope_id: envelope_id.to_string()
+        }
+    }
+}
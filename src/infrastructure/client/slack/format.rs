@@ -0,0 +1,91 @@
+use serde_json::{json, Value};
+use crate::domain::client::{Reply, Severity};
+
+const HEADER_LIMIT: usize = 150;
+const SECTION_LIMIT: usize = 2900;
+const FIELDS_PER_SECTION: usize = 10;
+
+/// Renders a reply as Block Kit blocks. Buttons are not rendered, since Socket Mode
+/// interactivity is not handled.
+pub fn to_blocks(reply: &Reply) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    let symbol = match reply.severity {
+        Severity::Info => None,
+        Severity::Success => Some(":white_check_mark:"),
+        Severity::Warning => Some(":warning:"),
+        Severity::Error => Some(":rotating_light:")
+    };
+
+    if let Some(title) = &reply.title {
+        let title = match symbol {
+            Some(symbol) => format!("{symbol} {title}"),
+            None => title.clone()
+        };
+        blocks.push(json!({
+            "type": "header",
+            "text": {"type": "plain_text", "text": truncate(title.as_str(), HEADER_LIMIT), "emoji": true}
+        }));
+    }
+
+    if !reply.text.is_empty() {
+        let text = match (symbol, &reply.title) {
+            (Some(symbol), None) => format!("{symbol} {}", escape(reply.text.as_str())),
+            _ => escape(reply.text.as_str())
+        };
+        blocks.extend(split(text.as_str(), SECTION_LIMIT).into_iter().map(section));
+    }
+
+    for fields in reply.fields.chunks(FIELDS_PER_SECTION) {
+        let fields: Vec<Value> = fields.iter()
+            .map(|(name, value)| json!({"type": "mrkdwn", "text": format!("*{}*\n{}", escape(name), escape(value))}))
+            .collect();
+        blocks.push(json!({"type": "section", "fields": fields}));
+    }
+
+    if let Some(code) = &reply.code {
+        blocks.extend(split(escape(code).as_str(), SECTION_LIMIT).into_iter()
+            .map(|code| section(format!("```{code}```"))));
+    }
+    blocks
+}
+
+fn section(text: String) -> Value {
+    json!({"type": "section", "text": {"type": "mrkdwn", "text": text}})
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    text.chars().take(limit).collect()
+}
+
+fn split(text: &str, limit: usize) -> Vec<String> {
+    text.chars()
+        .collect::<Vec<char>>()
+        .chunks(limit)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_blocks_renders_header_fields_and_code() {
+        let reply = Reply::titled("Health")
+            .with_severity(Severity::Warning)
+            .with_field("main", "Degraded")
+            .with_code("<html>");
+
+        assert_eq!(to_blocks(&reply), vec![
+            json!({"type": "header", "text": {"type": "plain_text", "text": ":warning: Health", "emoji": true}}),
+            json!({"type": "section", "fields": [{"type": "mrkdwn", "text": "*main*\nDegraded"}]}),
+            json!({"type": "section", "text": {"type": "mrkdwn", "text": "```&lt;html&gt;```"}})
+        ]);
+    }
+}
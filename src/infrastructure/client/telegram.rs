@@ -1,4 +1,6 @@
 pub mod dto;
+pub mod format;
+pub mod webhook;
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -6,32 +8,134 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use dto::SendMessageDto;
 use log::{debug, error, trace, warn};
+use reqwest::multipart::{Form, Part};
+use tokio::net::TcpListener;
 use tokio::sync::mpsc::{Sender};
+use uuid::Uuid;
 use crate::application::worker::Worker;
 use crate::domain;
+use crate::domain::client::{Button, Reply};
+use crate::domain::config::WebhookReceiverConfig;
 use crate::infrastructure::client::common::Client;
-use crate::infrastructure::client::telegram::dto::{GetUpdateDto, Message, TelegramResponse, Update};
+use crate::infrastructure::client::telegram::dto::{AnswerCallbackQueryDto, GetUpdateDto, InlineKeyboardButton, Message, ReplyMarkup, SetWebhookDto, TelegramResponse, Update};
 use crate::infrastructure::common::api_client::ApiClient;
 
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+const CALLBACK_DATA_LIMIT: usize = 64;
+const CAPTION_LIMIT: usize = 1024;
+
 #[derive(Clone)]
 pub struct TelegramClient {
     name: String,
     api_client: Arc<ApiClient>,
     offset: i64,
+    webhook: Option<WebhookReceiverConfig>,
+    webhook_deleted: bool,
     tx: Option<Sender<domain::client::Message>>
 }
 
 impl TelegramClient {
     pub fn new(name: String, token: String) -> Self {
+        Self::with_base_url(name, token, String::from(TELEGRAM_API_URL))
+    }
+
+    pub fn with_base_url(name: String, token: String, base_url: String) -> Self {
         trace!("TelegramClient::new(name: {}, token: ...)", &name);
         Self {
             name,
-            api_client: Arc::new(ApiClient::new(format!("https://api.telegram.org/bot{token}"))),
+            api_client: Arc::new(ApiClient::new(format!("{}/bot{token}", base_url.trim_end_matches('/')))),
             offset: 0,
+            webhook: None,
+            webhook_deleted: false,
             tx: None
         }
     }
 
+    pub fn with_webhook(mut self, webhook: WebhookReceiverConfig) -> Self {
+        self.webhook = Some(webhook);
+        self
+    }
+
+    async fn call<TBody>(&self, method: &str, dto: &TBody) -> Result<()>
+    where
+        TBody: serde::Serialize + Sync
+    {
+        let response = self.api_client
+            .post_json::<TBody, TelegramResponse<serde_json::Value>>(method, dto, None, None)
+            .await
+            .map_err(|e| anyhow!("[TelegramClient] Err: {}", e))?;
+        if !response.ok {
+            return Err(anyhow!("[TelegramClient] {}: {}", method, response.description.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
+    async fn serve_webhook(&self, webhook: &WebhookReceiverConfig) -> Result<()> {
+        trace!("TelegramClient::serve_webhook");
+        let secret_token = webhook.secret_token.clone()
+            .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+
+        let listener = TcpListener::bind(webhook.listen_addr.as_str()).await?;
+        self.call("setWebhook", &SetWebhookDto::new(webhook.url.as_str(), secret_token.as_str())).await?;
+        debug!("webhook registered: {} (listening on {})", &webhook.url, &webhook.listen_addr);
+
+        let router = webhook::router(self.clone(), secret_token);
+        axum::serve(listener, router).await?;
+        Ok(())
+    }
+
+    fn to_message(name: &str, update: Update) -> Option<domain::client::Message> {
+        let (chat_id, data) = if let Some(msg) = update.message {
+            (msg.chat.id.to_string(), msg.text.unwrap_or("".to_string()))
+        } else if let Some(cb) = update.callback_query {
+            let chat_id = cb.message?.chat.id.to_string();
+            let text = cb.data.clone().unwrap_or_default();
+            (chat_id, text)
+        } else {
+            return None;
+        };
+        Some(domain::client::Message::new(name.to_string(), chat_id, data))
+    }
+
+    fn to_reply_markup(buttons: &[Vec<Button>]) -> Option<ReplyMarkup> {
+        let inline_keyboard: Vec<Vec<InlineKeyboardButton>> = buttons.iter()
+            .map(|row| row.iter()
+                // Telegram rejects the whole message if any callback data is too long
+                .filter(|button| button.data.len() <= CALLBACK_DATA_LIMIT)
+                .map(|button| InlineKeyboardButton {
+                    text: button.text.clone(),
+                    url: None,
+                    callback_data: Some(button.data.clone())
+                })
+                .collect::<Vec<_>>())
+            .filter(|row| !row.is_empty())
+            .collect();
+
+        if inline_keyboard.is_empty() {
+            return None;
+        }
+        Some(ReplyMarkup { inline_keyboard })
+    }
+
+    /// Answers a pressed button (so the client stops spinning) and forwards the update as a message.
+    async fn handle_update(&self, update: Update) {
+        if let Some(callback_query) = &update.callback_query
+            && let Err(e) = self.call("answerCallbackQuery", &AnswerCallbackQueryDto::new(callback_query.id.as_str())).await {
+            warn!("[TelegramClient] Fail to answer callback query: {e}");
+        }
+
+        let message = match Self::to_message(self.get_name(), update) {
+            Some(message) => message,
+            None => return
+        };
+        debug!("created message: {:?}", &message);
+
+        if let Some(tx) = &self.tx
+            && let Err(e) = tx.send(message).await {
+            warn!("[TelegramClient] Err: {}", e);
+        }
+    }
+
     async fn get_update(&mut self) -> Result<Vec<Update>> {
         trace!("TelegramClient::get_update");
         let offset = self.offset;
@@ -84,6 +188,50 @@ impl Client for TelegramClient {
         self.send_message_direct(SendMessageDto::new(chat_id, data, None)).await
     }
 
+    async fn send_reply(&self, chat_id: &str, reply: &Reply) -> bool {
+        trace!("Client::send_reply(chat_id: {}, reply: ...)", chat_id);
+        let reply_markup = Self::to_reply_markup(&reply.buttons);
+        let dto = SendMessageDto::new(chat_id, format::to_html(reply).as_str(), reply_markup)
+            .with_parse_mode("HTML");
+        self.send_message_direct(dto).await
+    }
+
+    async fn send_file(&self, chat_id: &str, file_name: &str, data: &[u8], caption: &Reply) -> bool {
+        trace!("Client::send_file(chat_id: {}, file_name: {}, data: {} bytes)", chat_id, file_name, data.len());
+        let mut form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .part("document", Part::bytes(data.to_vec()).file_name(file_name.to_string()));
+
+        if !caption.is_empty() {
+            let mut html = format::to_html(caption);
+            if html.chars().count() > CAPTION_LIMIT {
+                // Cutting rendered html could leave a tag open, so fall back to escaped plain text
+                let plain: String = caption.to_plain_text().chars().take(CAPTION_LIMIT - 3).collect();
+                html = format!("{}...", format::escape(plain.as_str()));
+            }
+            form = form.text("caption", html).text("parse_mode", "HTML");
+        }
+        if let Some(reply_markup) = Self::to_reply_markup(&caption.buttons)
+            && let Ok(reply_markup) = serde_json::to_string(&reply_markup) {
+            form = form.text("reply_markup", reply_markup);
+        }
+
+        let response = self.api_client
+            .post_multipart::<TelegramResponse<serde_json::Value>>("sendDocument", form, None, None)
+            .await;
+        match response {
+            Ok(response) if response.ok => true,
+            Ok(response) => {
+                error!("[TelegramClient] sendDocument: {}", response.description.unwrap_or_default());
+                false
+            },
+            Err(e) => {
+                error!("[TelegramClient] Err: {e}");
+                false
+            }
+        }
+    }
+
     fn subscribe(&mut self, tx: Sender<domain::client::Message>) {
         trace!("Client::subscribe");
         self.tx = Some(tx);
@@ -94,6 +242,23 @@ impl Client for TelegramClient {
 impl Worker for TelegramClient {
     async fn on_tick(&mut self) -> bool {
         trace!("Worker::on_tick for {}", &self.name);
+        if let Some(webhook) = &self.webhook {
+            // One tick lasts for the lifetime of the webhook listener
+            if let Err(e) = self.serve_webhook(webhook).await {
+                error!("[TelegramClient] Err: {e}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            return true;
+        }
+
+        // getUpdates is rejected while a webhook from a previous run is still set
+        if !self.webhook_deleted {
+            match self.call("deleteWebhook", &serde_json::json!({})).await {
+                Ok(_) => self.webhook_deleted = true,
+                Err(e) => warn!("[TelegramClient] Fail to delete webhook: {e}")
+            }
+        }
+
         let updates = match self.get_update().await {
             Ok(updates) => {
                 updates
@@ -107,28 +272,7 @@ impl Worker for TelegramClient {
         debug!("{} updates received", updates.len());
 
         for update in updates {
-            let (chat_id, data) = if let Some(msg) = update.message {
-                (msg.chat.id.to_string(), msg.text.unwrap_or("".to_string()))
-            } else if let Some(cb) = update.callback_query {
-                let chat_id = match cb.message {
-                    Some(message) => message.chat.id.to_string(),
-                    None => continue
-                };
-                let text = cb.data.clone().unwrap_or_default();
-                (chat_id, text)
-            } else {
-                continue;
-            };
-            let message = domain::client::Message::new(
-                self.get_name().to_string(), chat_id, data
-            );
-            debug!("created message: {:?}", &message);
-
-            if let Some(tx) = &self.tx {
-                if let Err(e) = tx.send(message).await {
-                    warn!("[TelegramClient] Err: {}", e);
-                }
-            }
+            self.handle_update(update).await;
         }
         true
     }
@@ -149,6 +293,9 @@ mod tests {
     use std::env;
     use super::*;
     use dotenv::dotenv;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use crate::infrastructure::common::stub_server::StubServer;
 
     #[tokio::test]
     async fn get_update() {
@@ -166,6 +313,112 @@ mod tests {
         assert_eq!(response.unwrap().len(), 0);
     }
 
+    #[tokio::test]
+    async fn on_tick_serves_webhook() {
+        let server = StubServer::start(vec![
+            ("/bottoken/setWebhook", String::from(r#"{"ok":true,"result":true}"#))
+        ]).await;
+        let listen_addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let webhook = WebhookReceiverConfig {
+            url: String::from("https://watchdog.example.org/telegram"),
+            listen_addr: listen_addr.clone(),
+            secret_token: Some(String::from("secret"))
+        };
+        let mut client = TelegramClient::with_base_url("telegram".to_string(), "token".to_string(), server.base_url.clone())
+            .with_webhook(webhook);
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+        tokio::spawn(async move { client.on_tick().await });
+
+        let update = json!({
+            "update_id": 1,
+            "message": {
+                "message_id": 2, "date": 0, "text": "/health",
+                "from": {"id": 3, "is_bot": false, "first_name": "a"},
+                "chat": {"id": 4, "type": "private"}
+            }
+        });
+        let http = reqwest::Client::new();
+        let url = format!("http://{listen_addr}/telegram");
+        let post = |secret: &'static str| http.post(url.as_str()).header(webhook::SECRET_TOKEN_HEADER, secret).json(&update).send();
+        let response = loop {
+            match post("wrong").await {
+                Ok(response) => break response,
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await
+            }
+        };
+        assert_eq!(response.status(), 401);
+        assert_eq!(post("secret").await.unwrap().status(), 200);
+
+        let message = rx.recv().await.unwrap();
+        assert_eq!(message.chat_id, "4");
+        assert_eq!(message.data, "/health");
+        assert!(rx.try_recv().is_err());
+
+        let body: serde_json::Value = serde_json::from_str(server.requests()[0].body.as_str()).unwrap();
+        assert_eq!(body["url"], "https://watchdog.example.org/telegram");
+        assert_eq!(body["secret_token"], "secret");
+    }
+
+    #[tokio::test]
+    async fn on_tick_answers_callback_query() {
+        let update = json!({
+            "ok": true,
+            "result": [{
+                "update_id": 7,
+                "callback_query": {
+                    "id": "cb-1", "chat_instance": "1", "data": "/health main",
+                    "from": {"id": 3, "is_bot": false, "first_name": "a"},
+                    "message": {
+                        "message_id": 2, "date": 0,
+                        "from": {"id": 5, "is_bot": true, "first_name": "watchdog"},
+                        "chat": {"id": 4, "type": "private"}
+                    }
+                }
+            }]
+        });
+        let server = StubServer::start(vec![
+            ("/bottoken/deleteWebhook", String::from(r#"{"ok":true,"result":true}"#)),
+            ("/bottoken/getUpdates", update.to_string()),
+            ("/bottoken/answerCallbackQuery", String::from(r#"{"ok":true,"result":true}"#))
+        ]).await;
+        let mut client = TelegramClient::with_base_url("telegram".to_string(), "token".to_string(), server.base_url.clone());
+        let (tx, mut rx) = mpsc::channel(16);
+        client.subscribe(tx);
+
+        assert!(client.on_tick().await);
+
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.chat_id, "4");
+        assert_eq!(message.data, "/health main");
+
+        let requests = server.requests();
+        let answer = requests.iter().find(|request| request.path.ends_with("/answerCallbackQuery")).unwrap();
+        let body: serde_json::Value = serde_json::from_str(answer.body.as_str()).unwrap();
+        assert_eq!(body["callback_query_id"], "cb-1");
+    }
+
+    #[tokio::test]
+    async fn send_reply_with_inline_keyboard() {
+        let server = StubServer::start(vec![
+            ("/bottoken/sendMessage", String::from(r#"{"ok":true,"result":{"message_id":1,"date":0,"from":{"id":5,"is_bot":true,"first_name":"watchdog"},"chat":{"id":4,"type":"private"}}}"#))
+        ]).await;
+        let client = TelegramClient::with_base_url("telegram".to_string(), "token".to_string(), server.base_url.clone());
+        let reply = Reply::titled("Health").with_field("main", "Healthy").with_buttons(vec![
+            vec![Button::new(String::from("main"), String::from("/health main"))],
+            vec![Button::new(String::from("too long"), format!("/health {}", "a".repeat(CALLBACK_DATA_LIMIT)))]
+        ]);
+
+        assert!(client.send_reply("4", &reply).await);
+
+        let body: serde_json::Value = serde_json::from_str(server.requests()[0].body.as_str()).unwrap();
+        assert_eq!(body["text"], "<b>Health</b>\n<b>main</b>: Healthy");
+        assert_eq!(body["parse_mode"], "HTML");
+        assert_eq!(body["reply_markup"], json!({
+            "inline_keyboard": [[{"text": "main", "callback_data": "/health main"}]]
+        }));
+    }
+
     #[tokio::test]
     async fn send_message() {
         dotenv().ok();
@@ -5,6 +5,8 @@ pub struct SendMessageDto {
     chat_id: String,
     text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    parse_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     reply_markup: Option<ReplyMarkup>
 }
 
@@ -27,7 +29,7 @@ impl GetUpdateDto {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ReplyMarkup {
-    pub inline_keyboard: Vec<InlineKeyboardButton>
+    pub inline_keyboard: Vec<Vec<InlineKeyboardButton>>
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -52,9 +54,15 @@ impl SendMessageDto {
         Self {
             chat_id: chat_id.to_string(),
             text: text.to_string(),
+            parse_mode: None,
             reply_markup
         }
     }
+
+    pub fn with_parse_mode(mut self, parse_mode: &str) -> Self {
+        self.parse_mode = Some(parse_mode.to_string());
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -105,4 +113,33 @@ pub struct User {
     id: i64,
     is_bot: bool,
     first_name: String
-}
\ No newline at end of file
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetWebhookDto {
+    url: String,
+    secret_token: String,
+    allowed_updates: Vec<String>
+}
+
+impl SetWebhookDto {
+    pub fn new(url: &str, secret_token: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            secret_token: secret_token.to_string(),
+            allowed_updates: vec![String::from("message"), String::from("callback_query")]
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AnswerCallbackQueryDto {
+    callback_query_id: String
+}
+
+impl AnswerCallbackQueryDto {
+    pub fn new(callback_query_id: &str) -> Self {
+        Self {
+            callback_query_id: callback_query_id.to_string()
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::domain::client::{Reply, Severity};
+
+/// Renders a reply for `parse_mode: HTML`.
+pub fn to_html(reply: &Reply) -> String {
+    let mut lines = Vec::new();
+    if let Some(title) = &reply.title {
+        lines.push(format!("<b>{}</b>", escape(title)));
+    }
+    if !reply.text.is_empty() {
+        lines.push(escape(reply.text.as_str()));
+    }
+    for (name, value) in &reply.fields {
+        lines.push(format!("<b>{}</b>: {}", escape(name), escape(value)));
+    }
+    if let Some(code) = &reply.code {
+        lines.push(format!("<pre>{}</pre>", escape(code)));
+    }
+
+    let html = lines.join("\n");
+    match reply.severity {
+        Severity::Info => html,
+        Severity::Success => format!("✅ {html}"),
+        Severity::Warning => format!("⚠️ {html}"),
+        Severity::Error => format!("🚨 {html}")
+    }
+}
+
+/// Telegram only requires these three characters to be escaped outside of tags.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_html_escapes_every_part() {
+        let reply = Reply::titled("Logs: <main>")
+            .with_severity(Severity::Error)
+            .with_text("a & b")
+            .with_field("Health", "Down <5xx>")
+            .with_code("if a < b && c > d {}");
+
+        assert_eq!(to_html(&reply), "🚨 <b>Logs: &lt;main&gt;</b>\n\
+            a &amp; b\n\
+            <b>Health</b>: Down &lt;5xx&gt;\n\
+            <pre>if a &lt; b &amp;&amp; c &gt; d {}</pre>");
+    }
+}
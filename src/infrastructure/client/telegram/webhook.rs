@@ -0,0 +1,38 @@
+use std::sync::Arc;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use log::{debug, warn};
+use subtle::ConstantTimeEq;
+use crate::infrastructure::client::telegram::dto::Update;
+use crate::infrastructure::client::telegram::TelegramClient;
+
+pub const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+struct WebhookState {
+    client: TelegramClient,
+    secret_token: String
+}
+
+/// Accepts updates pushed by Telegram on any path, so a reverse proxy may forward any prefix.
+pub fn router(client: TelegramClient, secret_token: String) -> Router {
+    let state = Arc::new(WebhookState { client, secret_token });
+    Router::new()
+        .route("/", post(receive))
+        .route("/{*path}", post(receive))
+        .with_state(state)
+}
+
+async fn receive(State(state): State<Arc<WebhookState>>, headers: HeaderMap, Json(update): Json<Update>) -> StatusCode {
+    let secret_token = headers.get(SECRET_TOKEN_HEADER).map(|value| value.as_bytes()).unwrap_or_default();
+    // Constant time, so the token cannot be guessed byte by byte from response times
+    if !bool::from(secret_token.ct_eq(state.secret_token.as_bytes())) {
+        warn!("[TelegramClient] Webhook request with invalid secret token");
+        return StatusCode::UNAUTHORIZED;
+    }
+    debug!("webhook update: {:?}", &update);
+
+    state.client.handle_update(update).await;
+    StatusCode::OK
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, error, trace};
+use serde_json::Value;
+use tokio::sync::mpsc::Sender;
+use crate::application::worker::Worker;
+use crate::domain;
+use crate::infrastructure::client::common::Client;
+
+const DEFAULT_BODY_TEMPLATE: &str = r#"{"text": "{{message}}"}"#;
+
+/// Outbound only client which posts every message to a fixed http endpoint.
+#[derive(Clone)]
+pub struct WebhookClient {
+    name: String,
+    url: String,
+    headers: HashMap<String, String>,
+    body_template: Value,
+    http: reqwest::Client
+}
+
+impl WebhookClient {
+    pub fn new(name: String, url: String, headers: Option<HashMap<String, String>>, body_template: Option<String>) -> Result<Self> {
+        trace!("WebhookClient::new(name: {}, url: ...)", &name);
+        let body_template = serde_json::from_str(body_template.as_deref().unwrap_or(DEFAULT_BODY_TEMPLATE))?;
+        Ok(Self {
+            name,
+            url,
+            headers: headers.unwrap_or_default(),
+            body_template,
+            http: reqwest::Client::new()
+        })
+    }
+
+    /// Replaces placeholders inside string values, so the message is always escaped as json.
+    fn render(template: &Value, chat_id: &str, message: &str) -> Value {
+        match template {
+            Value::String(text) => Value::String(
+                text.replace("{{chat_id}}", chat_id).replace("{{message}}", message)
+            ),
+            Value::Array(values) => Value::Array(
+                values.iter().map(|value| Self::render(value, chat_id, message)).collect()
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter().map(|(key, value)| (key.clone(), Self::render(value, chat_id, message))).collect()
+            ),
+            other => other.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl Client for WebhookClient {
+
+    async fn send_message(&self, chat_id: &str, data: &str) -> bool {
+        trace!("Client::send_message(chat_id: {}, data: ...)", chat_id);
+        let body = Self::render(&self.body_template, chat_id, data);
+        debug!("webhook body: {}", &body);
+
+        let mut request = self.http.post(self.url.as_str()).json(&body);
+        for (key, value) in &self.headers {
+            request = request.header(key.as_str(), value.as_str());
+        }
+
+        match request.send().await.and_then(|response| response.error_for_status()) {
+            Ok(_) => true,
+            Err(e) => {
+                error!("[WebhookClient] Err: {e}");
+                false
+            }
+        }
+    }
+
+    fn subscribe(&mut self, _tx: Sender<domain::client::Message>) {
+        trace!("Client::subscribe");
+    }
+}
+
+#[async_trait]
+impl Worker for WebhookClient {
+    async fn on_tick(&mut self) -> bool {
+        // Nothing to receive, stop ticking
+        false
+    }
+
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn interval(&self) -> i32 {
+        5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::infrastructure::common::stub_server::StubServer;
+
+    #[tokio::test]
+    async fn send_message_renders_template() {
+        let server = StubServer::start(vec![
+            ("/hooks/alarm", String::from("{}"))
+        ]).await;
+        let headers = HashMap::from([(String::from("X-Api-Key"), String::from("secret"))]);
+        let client = WebhookClient::new(
+            "webhook".to_string(),
+            format!("{}/hooks/alarm?team=ops", server.base_url),
+            Some(headers),
+            Some(String::from(r#"{"channel": "{{chat_id}}", "blocks": [{"text": "[alarm] {{message}}"}], "priority": 1}"#))
+        ).unwrap();
+
+        assert!(client.send_message("ops", "line \"1\"\nline 2").await);
+
+        let request = &server.requests()[0];
+        assert_eq!(request.path, "/hooks/alarm?team=ops");
+        assert_eq!(request.header("x-api-key"), Some("secret"));
+        let body: Value = serde_json::from_str(request.body.as_str()).unwrap();
+        assert_eq!(body, json!({"channel": "ops", "blocks": [{"text": "[alarm] line \"1\"\nline 2"}], "priority": 1}));
+    }
+
+    #[tokio::test]
+    async fn send_message_fails_on_error_status() {
+        let server = StubServer::start(vec![]).await;
+        let client = WebhookClient::new("webhook".to_string(), format!("{}/missing", server.base_url), None, None).unwrap();
+
+        assert!(!client.send_message("ops", "hello").await);
+    }
+
+    #[test]
+    fn new_rejects_invalid_template() {
+        assert!(WebhookClient::new("webhook".to_string(), String::from("http://localhost"), None, Some(String::from("{text}"))).is_err());
+    }
+}
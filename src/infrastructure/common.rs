@@ -1,2 +1,8 @@
 pub mod api_client;
-pub mod file_accessor;
\ No newline at end of file
+pub mod file_accessor;
+pub mod password;
+pub mod totp;
+#[cfg(test)]
+pub mod stub_server;
+#[cfg(test)]
+pub mod memory_file_accessor;
\ No newline at end of file
@@ -26,11 +26,16 @@ impl ApiClient {
                  path: &str,
                  params: Option<&[(&str, &str)]>
     ) -> String {
-        let mut url = format!(
-            "{}/{}",
-            self.base_url.trim_end_matches('/'),
-            path.trim_start_matches('/')
-        );
+        // An empty path targets the base url itself, e.g. a pre-signed upload url
+        let mut url = if path.is_empty() {
+            self.base_url.clone()
+        } else {
+            format!(
+                "{}/{}",
+                self.base_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            )
+        };
 
         if let Some(params) = params {
             if !params.is_empty() {
@@ -128,6 +133,45 @@ impl ApiClient {
         Ok(resp.json::<TResp>().await?)
     }
 
+    // -----------------------
+    //   POST multipart/form-data
+    // -----------------------
+    pub async fn post_multipart<TResp>(
+        &self,
+        path: &str,
+        form: reqwest::multipart::Form,
+        extra_headers: Option<&[(&str, &str)]>,
+        params: Option<&[(&str, &str)]>,
+    ) -> Result<TResp, Box<dyn std::error::Error + Send + Sync>>
+    where
+        TResp: DeserializeOwned,
+    {
+        let url = self.build_url(path, params);
+        let req = self.http.post(&url).multipart(form);
+        let req = self.apply_headers(req, extra_headers);
+
+        let resp = req.send().await?.error_for_status()?;
+        Ok(resp.json::<TResp>().await?)
+    }
+
+    // -----------------------
+    //   POST raw bytes
+    // -----------------------
+    pub async fn post_bytes(
+        &self,
+        path: &str,
+        body: Vec<u8>,
+        extra_headers: Option<&[(&str, &str)]>,
+        params: Option<&[(&str, &str)]>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let url = self.build_url(path, params);
+        let req = self.http.post(&url).body(body);
+        let req = self.apply_headers(req, extra_headers);
+
+        req.send().await?.error_for_status()?;
+        Ok(())
+    }
+
     // -----------------------
     //   PUT application/json
     // -----------------------
@@ -6,9 +6,14 @@ use derive_new::new;
 use serde::{Serialize};
 use serde::de::DeserializeOwned;
 use tokio::fs;
+use crate::domain::alert::AlertList;
 use crate::domain::chat::ChatList;
 use crate::domain::config::{Config, EventSubscribeList};
 use crate::domain::file_accessor::FileAccessor;
+use crate::domain::lockout::LockoutState;
+use crate::domain::silence::SilenceList;
+use crate::domain::totp::TotpState;
+use crate::infrastructure::client::matrix::dto::SyncState;
 use async_trait::async_trait;
 
 #[derive(new, Clone)]
@@ -92,4 +97,39 @@ pub fn get_event_subscribe_file_accessor() -> JsonFileAccessor<EventSubscribeLis
         String::from("subscribe.json"),
         Arc::new(||{EventSubscribeList::new()})
     )
+}
+
+pub fn get_lockout_file_accessor() -> JsonFileAccessor<LockoutState> {
+    JsonFileAccessor::new(
+        String::from("lockout.json"),
+        Arc::new(||{LockoutState::new()})
+    )
+}
+
+pub fn get_totp_state_file_accessor() -> JsonFileAccessor<TotpState> {
+    JsonFileAccessor::new(
+        String::from("totp_state.json"),
+        Arc::new(||{TotpState::new()})
+    )
+}
+
+pub fn get_silence_file_accessor() -> JsonFileAccessor<SilenceList> {
+    JsonFileAccessor::new(
+        String::from("silence.json"),
+        Arc::new(||{SilenceList::new()})
+    )
+}
+
+pub fn get_alert_file_accessor() -> JsonFileAccessor<AlertList> {
+    JsonFileAccessor::new(
+        String::from("alert.json"),
+        Arc::new(||{AlertList::new()})
+    )
+}
+
+pub fn get_matrix_sync_file_accessor(client_name: &str) -> JsonFileAccessor<SyncState> {
+    JsonFileAccessor::new(
+        format!("matrix_{client_name}_sync.json"),
+        Arc::new(||{SyncState::new()})
+    )
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use crate::domain::file_accessor::FileAccessor;
+
+/// In-memory FileAccessor for tests, so nothing is written under ~/.watchdog.
+#[derive(Clone)]
+pub struct MemoryFileAccessor<T> {
+    data: Arc<Mutex<T>>
+}
+
+impl<T> MemoryFileAccessor<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data))
+        }
+    }
+}
+
+#[async_trait]
+impl<T> FileAccessor<T> for MemoryFileAccessor<T>
+where
+    T: Serialize + DeserializeOwned + Send + Sync + Clone
+{
+    async fn read(&self) -> Result<T, Box<dyn Error + Send + Sync>> {
+        Ok(self.data.lock().unwrap().clone())
+    }
+
+    async fn write(&self, data: &T) -> Result<(), Box<dyn Error + Send + Sync>> {
+        *self.data.lock().unwrap() = data.clone();
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+/// Hashes a password into a PHC string (`$argon2id$...`) with a random salt.
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default().hash_password(password.as_bytes(), &salt)?.to_string())
+}
+
+/// Verifies a password against a PHC string. The hash comparison is constant time.
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(_) => false
+    }
+}
+
+/// Whether a stored password is already hashed, as opposed to plaintext from older versions.
+pub fn is_hashed(value: &str) -> bool {
+    PasswordHash::new(value).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_hashed_password() {
+        let hash = hash_password("secret").unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(is_hashed(hash.as_str()));
+        assert!(!is_hashed("secret"));
+        assert!(verify_password(hash.as_str(), "secret"));
+        assert!(!verify_password(hash.as_str(), "Secret"));
+        assert!(!verify_password("secret", "secret"));
+    }
+}
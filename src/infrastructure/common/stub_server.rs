@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Minimal HTTP stand-in for exercising api clients in tests.
+/// Every request is recorded and answered with the first route whose path matches.
+pub struct StubServer {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl StubServer {
+    pub async fn start(routes: Vec<(&'static str, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let routes = routes.clone();
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    Self::serve(stream, routes, recorded).await;
+                });
+            }
+        });
+
+        Self {
+            base_url,
+            requests
+        }
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    async fn serve(mut stream: TcpStream, routes: Vec<(&'static str, String)>, recorded: Arc<Mutex<Vec<RecordedRequest>>>) {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+
+        let header_end = loop {
+            let n = match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => n
+            };
+            buffer.extend_from_slice(&chunk[..n]);
+            if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+                break position + 4;
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+        let mut lines = head.lines();
+        let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+        let method = request_line.next().unwrap_or_default().to_string();
+        let path = request_line.next().unwrap_or_default().to_string();
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        let content_length = headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+            .unwrap_or(0);
+
+        while buffer.len() < header_end + content_length {
+            let n = match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n
+            };
+            buffer.extend_from_slice(&chunk[..n]);
+        }
+        let body = String::from_utf8_lossy(&buffer[header_end..]).to_string();
+
+        let response = routes.iter()
+            .find(|(route, _)| path.starts_with(route))
+            .map(|(_, body)| body.clone());
+
+        recorded.lock().unwrap().push(RecordedRequest { method, path, headers, body });
+
+        let (status, body) = match response {
+            Some(body) => ("200 OK", body),
+            None => ("404 Not Found", String::from("{}"))
+        };
+        let raw = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let _ = stream.write_all(raw.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+}
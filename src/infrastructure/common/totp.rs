@@ -0,0 +1,87 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base32::Alphabet;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+const ISSUER: &str = "server-watchdog";
+const STEP: u64 = 30;
+const DIGITS: u32 = 6;
+/// Steps accepted before and after the current one, for clock drift and typing time
+const WINDOW: u64 = 1;
+
+/// A random 160 bit secret, base32 encoded as authenticator apps expect.
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 20];
+    OsRng.fill_bytes(&mut secret);
+    base32::encode(Alphabet::Rfc4648 { padding: false }, &secret)
+}
+
+/// `otpauth://` URI for enrolling the secret in an authenticator app, usually through a QR code.
+pub fn provisioning_uri(secret: &str, account: &str) -> String {
+    format!("otpauth://totp/{ISSUER}:{account}?secret={secret}&issuer={ISSUER}&algorithm=SHA1&digits={DIGITS}&period={STEP}")
+}
+
+/// HOTP value (RFC 4226) of a counter, truncated to `digits`.
+fn hotp(key: &[u8], counter: u64, digits: u32) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+    binary % 10u32.pow(digits)
+}
+
+/// Current code of a base32 secret, e.g. for tests or a companion tool.
+pub fn code(secret: &str, unix_time: u64) -> Option<String> {
+    let key = base32::decode(Alphabet::Rfc4648 { padding: false }, secret)?;
+    Some(format!("{:0width$}", hotp(&key, unix_time / STEP, DIGITS), width = DIGITS as usize))
+}
+
+/// Checks a code (RFC 6238) and returns the time step it matched, so callers can refuse replays.
+pub fn verify(secret: &str, code: &str, unix_time: u64) -> Option<u64> {
+    if code.len() != DIGITS as usize || !code.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let key = base32::decode(Alphabet::Rfc4648 { padding: false }, secret)?;
+
+    let current = unix_time / STEP;
+    (current.saturating_sub(WINDOW)..=current + WINDOW)
+        .find(|&counter| hotp(&key, counter, DIGITS) == code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vectors of RFC 6238, appendix B (SHA1)
+    #[test]
+    fn rfc6238_vectors() {
+        let key = b"12345678901234567890";
+        let vectors = [
+            (59, 94287082),
+            (1111111109, 7081804),
+            (1111111111, 14050471),
+            (1234567890, 89005924),
+            (2000000000, 69279037),
+            (20000000000, 65353130)
+        ];
+        for (time, expected) in vectors {
+            assert_eq!(hotp(key, time / STEP, 8), expected, "time {time}");
+        }
+    }
+
+    #[test]
+    fn verify_within_window() {
+        let secret = base32::encode(Alphabet::Rfc4648 { padding: false }, b"12345678901234567890");
+
+        assert_eq!(code(secret.as_str(), 59).unwrap(), "287082");
+        assert_eq!(verify(secret.as_str(), "287082", 59), Some(1));
+        assert_eq!(verify(secret.as_str(), "287082", 59 + STEP), Some(1));
+        assert_eq!(verify(secret.as_str(), "287082", 59 + 2 * STEP), None);
+        assert_eq!(verify(secret.as_str(), "287083", 59), None);
+        assert_eq!(verify(secret.as_str(), "28708", 59), None);
+        assert_eq!(generate_secret().len(), 32);
+    }
+}
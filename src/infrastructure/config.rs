@@ -2,7 +2,15 @@ pub mod auth;
 mod client;
 mod server;
 mod event;
+mod lockout;
+mod silence;
+mod maintenance;
+mod alert;
 
 pub use client::*;
 pub use server::*;
 pub use event::*;
+pub use lockout::*;
+pub use silence::*;
+pub use maintenance::*;
+pub use alert::*;
@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::sync::Arc;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use crate::application::config::AlertUseCase;
+use crate::domain::alert::{Alert, AlertList, ALERT_RETENTION};
+use crate::domain::file_accessor::FileAccessor;
+
+/// Shared by the event manager, the escalation worker and the handler,
+/// so updates are serialized to keep e.g. an `/ack` from being overwritten.
+#[derive(new)]
+pub struct AlertAdapter {
+    alert_file_accessor: Arc<dyn FileAccessor<AlertList>>,
+    #[new(default)]
+    lock: Mutex<()>,
+    #[new(value = "Box::new(random_id)")]
+    generate_id: Box<dyn Fn() -> String + Send + Sync>
+}
+
+/// Six hex digits, short enough to type after `/ack`.
+fn random_id() -> String {
+    Uuid::new_v4().simple().to_string()[..6].to_string()
+}
+
+impl AlertAdapter {
+    /// Replaces the random ids, e.g. to know them in tests.
+    pub fn with_id_generator(mut self, generate_id: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.generate_id = Box::new(generate_id);
+        self
+    }
+
+    async fn update<T>(&self, f: impl FnOnce(&mut AlertList) -> Result<T, Box<dyn Error + Send + Sync>> + Send)
+                       -> Result<T, Box<dyn Error + Send + Sync>> {
+        let _guard = self.lock.lock().await;
+        let mut alert_list = self.alert_file_accessor.read().await?;
+        let value = f(&mut alert_list)?;
+        self.alert_file_accessor.write(&alert_list).await?;
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl AlertUseCase for AlertAdapter {
+    async fn open_alert(&self, event_name: String, server_name: String, text: String) -> Result<Alert, Box<dyn Error + Send + Sync>> {
+        self.update(move |alert_list| {
+            // Short ids collide now and then, and `/ack` must not hit another alert
+            let mut id = (self.generate_id)();
+            while alert_list.alerts.iter().any(|alert| alert.id == id) {
+                id = (self.generate_id)();
+            }
+            let alert = Alert {
+                id,
+                event_name,
+                server_name,
+                text,
+                created_at: Utc::now(),
+                escalated: 0,
+                acknowledged_by: None
+            };
+            alert_list.alerts.push(alert.clone());
+            Ok(alert)
+        }).await
+    }
+
+    async fn find_alert(&self, id: &str) -> Result<Option<Alert>, Box<dyn Error + Send + Sync>> {
+        Ok(self.alert_file_accessor.read().await?
+            .alerts
+            .into_iter()
+            .find(|alert| alert.id == id))
+    }
+
+    async fn acknowledge(&self, id: &str, acknowledged_by: String) -> Result<Alert, Box<dyn Error + Send + Sync>> {
+        self.update(|alert_list| {
+            let alert = alert_list.find_mut(id)
+                .ok_or_else(|| anyhow!("Alert '{id}' not found"))?;
+            if let Some(by) = &alert.acknowledged_by {
+                return Err(anyhow!("Alert '{id}' is already acknowledged by {by}").into());
+            }
+            alert.acknowledged_by = Some(acknowledged_by);
+            Ok(alert.clone())
+        }).await
+    }
+
+    async fn list_unacknowledged(&self) -> Result<Vec<Alert>, Box<dyn Error + Send + Sync>> {
+        Ok(self.alert_file_accessor.read().await?
+            .alerts
+            .into_iter()
+            .filter(|alert| alert.acknowledged_by.is_none())
+            .collect())
+    }
+
+    async fn set_escalated(&self, id: &str, escalated: usize) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.update(|alert_list| {
+            if let Some(alert) = alert_list.find_mut(id) {
+                alert.escalated = escalated;
+            }
+            Ok(())
+        }).await
+    }
+
+    async fn prune(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let _guard = self.lock.lock().await;
+        let mut alert_list = self.alert_file_accessor.read().await?;
+        if alert_list.prune(Utc::now() - ALERT_RETENTION) {
+            self.alert_file_accessor.write(&alert_list).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use super::*;
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+
+    #[tokio::test]
+    async fn open_alert_skips_ids_in_use() {
+        let ids = Mutex::new(vec!["bbbbbb", "aaaaaa", "aaaaaa"]);
+        let alert_adapter = AlertAdapter::new(Arc::new(MemoryFileAccessor::new(AlertList::new())))
+            .with_id_generator(move || ids.lock().unwrap().pop().unwrap().to_string());
+
+        let first = alert_adapter.open_alert(String::from("main-down"), String::from("main"), String::from("main is down")).await.unwrap();
+        let second = alert_adapter.open_alert(String::from("db-down"), String::from("db"), String::from("db is down")).await.unwrap();
+
+        assert_eq!(first.id, "aaaaaa");
+        assert_eq!(second.id, "bbbbbb");
+        alert_adapter.acknowledge("aaaaaa", String::from("telegram:1")).await.unwrap();
+        assert!(alert_adapter.find_alert("bbbbbb").await.unwrap().unwrap().acknowledged_by.is_none());
+    }
+}
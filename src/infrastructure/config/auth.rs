@@ -1,44 +1,89 @@
 use std::error::Error;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::anyhow;
 use async_trait::async_trait;
+use log::{error, info};
+use tokio::time::Instant;
 use crate::application::config::AuthUseCase;
-use crate::domain::chat::{Chat, ChatList, ChatMap};
+use crate::domain::chat::{Chat, ChatList, ChatMap, Role};
 use crate::domain::config::Config;
 use crate::domain::file_accessor::FileAccessor;
+use crate::domain::totp::TotpState;
+use crate::infrastructure::common::{password, totp};
+
+/// Bounds how long a running watchdog keeps chats changed by another process, e.g. the CLI.
+const CHAT_MAP_TTL: Duration = Duration::from_secs(30);
 
 pub struct AuthAdapter {
     password: Option<String>,
-    chat_map: Option<ChatMap>,
+    totp_secret: Option<String>,
+    chat_map: Option<(ChatMap, Instant)>,
     config_file_accessor: Arc<dyn FileAccessor<Config> + Send + Sync>,
-    chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync>
+    chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync>,
+    totp_state_file_accessor: Arc<dyn FileAccessor<TotpState> + Send + Sync>
 }
 
 impl AuthAdapter {
     pub fn new(
         config_file_accessor: Arc<dyn FileAccessor<Config> + Send + Sync>,
-        chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync>
+        chat_list_file_accessor: Arc<dyn FileAccessor<ChatList> + Send + Sync>,
+        totp_state_file_accessor: Arc<dyn FileAccessor<TotpState> + Send + Sync>
     ) -> Self {
         Self {
             password: None,
+            totp_secret: None,
             chat_map: None,
             config_file_accessor,
-            chat_list_file_accessor
+            chat_list_file_accessor,
+            totp_state_file_accessor
         }
     }
 
     pub async fn init(&mut self) {
-        let config = self.config_file_accessor.read().await
+        let mut config = self.config_file_accessor.read().await
             .unwrap();
+
+        // Passwords stored in plaintext by older versions are hashed on first read
+        if let Some(plaintext) = config.password.as_deref()
+            && !password::is_hashed(plaintext) {
+            match password::hash_password(plaintext) {
+                Ok(hash) => {
+                    config.password = Some(hash);
+                    match self.config_file_accessor.write(&config).await {
+                        Ok(_) => info!("[AuthAdapter] Info: Plaintext password migrated to a hash"),
+                        Err(e) => error!("[AuthAdapter] Err: Fail to store hashed password: {e}")
+                    }
+                },
+                Err(e) => error!("[AuthAdapter] Err: Fail to hash password: {e}")
+            }
+        }
         self.password = config.password;
+        self.totp_secret = config.totp_secret;
     }
 
     async fn get_chat_map(&mut self) -> Result<&ChatMap, Box<dyn Error + Send + Sync>> {
-        if self.chat_map.is_none() {
+        let expired = match &self.chat_map {
+            Some((_, loaded_at)) => loaded_at.elapsed() >= CHAT_MAP_TTL,
+            None => true
+        };
+        if expired {
             let list = self.chat_list_file_accessor.read().await?;
-            self.chat_map = Some(ChatMap::from(list));
+            self.chat_map = Some((ChatMap::from(list), Instant::now()));
         }
 
-        Ok(self.chat_map.as_ref().unwrap())
+        Ok(&self.chat_map.as_ref().unwrap().0)
+    }
+
+    async fn update_chat(&mut self, chat_id: &str, update: impl FnOnce(&mut Chat) + Send) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut chat_list = self.chat_list_file_accessor.read().await?;
+        let chat = chat_list.chats.iter_mut()
+            .find(|chat| chat.id == chat_id)
+            .ok_or_else(|| anyhow!("Chat '{chat_id}' is not registered"))?;
+        update(chat);
+        self.chat_list_file_accessor.write(&chat_list).await?;
+        self.chat_map = None;
+        Ok(())
     }
 }
 
@@ -46,45 +91,193 @@ impl AuthAdapter {
 impl AuthUseCase for AuthAdapter {
     async fn set_password(&self, password: Option<String>) -> Result<(), Box<dyn Error + Send + Sync>> {
         let mut config = self.config_file_accessor.read().await?;
-        config.password = password;
+        config.password = match password {
+            Some(password) => Some(password::hash_password(password.as_str())?),
+            None => {
+                // A code alone must not be enough once the password is set again
+                config.totp_secret = None;
+                None
+            }
+        };
         self.config_file_accessor.write(&config).await?;
         Ok(())
     }
 
     async fn validate_password(&mut self, password: String) -> bool {
         let config_password = self.password.as_ref().expect("Password is not defined").as_str();
-        config_password.eq(password.as_str())
+        password::verify_password(config_password, password.as_str())
     }
 
-    async fn register(&mut self, client_name: String, identity: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn register(&mut self, client_name: String, identity: String) -> Result<String, Box<dyn Error + Send + Sync>> {
         let mut chat_list = self.chat_list_file_accessor.read()
             .await?;
         
         // Check if the entry already exists to make registration idempotent
-        let already_exists = chat_list.chats.iter().any(|chat| {
+        let existing = chat_list.chats.iter().find(|chat| {
             chat.client_name == client_name && chat.identity == identity
         });
         
-        if !already_exists {
-            chat_list.chats.push(Chat::new(client_name, identity));
-            self.chat_list_file_accessor.write(&chat_list).await?;
-            self.chat_map = None;
+        if let Some(chat) = existing {
+            return Ok(chat.id.clone());
         }
-        
-        Ok(())
+
+        let chat = Chat::new(client_name, identity);
+        let id = chat.id.clone();
+        chat_list.chats.push(chat);
+        self.chat_list_file_accessor.write(&chat_list).await?;
+        self.chat_map = None;
+
+        Ok(id)
     }
 
-    async fn authenticate(&mut self, client_name: String, identity: String) -> Option<String> {
+    async fn authenticate(&mut self, client_name: String, identity: String) -> Option<Chat> {
         let chat_map = match self.get_chat_map().await {
             Ok(value) => value,
             Err(_) => return None
         };
 
-        let id = chat_map.get_id(client_name.as_str(), identity.as_str())?;
-        Some(String::from(id))
+        chat_map.get(client_name.as_str(), identity.as_str()).cloned()
+    }
+
+    async fn list_chats(&self) -> Result<Vec<Chat>, Box<dyn Error + Send + Sync>> {
+        Ok(self.chat_list_file_accessor.read().await?.chats)
+    }
+
+    async fn revoke(&mut self, chat_id: &str) -> Result<Chat, Box<dyn Error + Send + Sync>> {
+        let mut chat_list = self.chat_list_file_accessor.read().await?;
+        let chat = chat_list.remove(chat_id)
+            .ok_or_else(|| anyhow!("Chat '{chat_id}' is not registered"))?;
+        self.chat_list_file_accessor.write(&chat_list).await?;
+
+        if let Some((chat_map, _)) = self.chat_map.as_mut() {
+            chat_map.remove(chat.client_name.as_str(), chat.identity.as_str());
+        }
+        Ok(chat)
+    }
+
+    async fn set_role(&mut self, chat_id: &str, role: Role) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.update_chat(chat_id, |chat| chat.role = role).await
+    }
+
+    async fn set_servers(&mut self, chat_id: &str, servers: Option<Vec<String>>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.update_chat(chat_id, |chat| chat.servers = servers).await
     }
 
     fn password_required(&self) -> bool {
         self.password.is_some()
     }
-}
\ No newline at end of file
+
+    async fn set_totp_secret(&self, secret: Option<String>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut config = self.config_file_accessor.read().await?;
+        config.totp_secret = secret;
+        self.config_file_accessor.write(&config).await?;
+        Ok(())
+    }
+
+    async fn validate_totp(&mut self, code: &str) -> bool {
+        let Some(secret) = self.totp_secret.as_deref() else {
+            return false;
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let Some(step) = totp::verify(secret, code, now) else {
+            return false;
+        };
+
+        // Fails closed, as a step which cannot be stored could be used again
+        let mut state = match self.totp_state_file_accessor.read().await {
+            Ok(state) => state,
+            Err(e) => {
+                error!("[AuthAdapter] Err: Fail to read last TOTP step: {e}");
+                return false;
+            }
+        };
+        if state.last_step.is_some_and(|last| step <= last) {
+            return false;
+        }
+        state.last_step = Some(step);
+        match self.totp_state_file_accessor.write(&state).await {
+            Ok(_) => true,
+            Err(e) => {
+                error!("[AuthAdapter] Err: Fail to store last TOTP step: {e}");
+                false
+            }
+        }
+    }
+
+    fn totp_required(&self) -> bool {
+        self.totp_secret.is_some()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::common::memory_file_accessor::MemoryFileAccessor;
+
+    #[tokio::test]
+    async fn init_migrates_plaintext_password() {
+        let config_file_accessor = Arc::new(MemoryFileAccessor::new(Config::new(Some(String::from("secret")))));
+        let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), Arc::new(MemoryFileAccessor::new(ChatList::new())), Arc::new(MemoryFileAccessor::new(TotpState::new())));
+
+        auth_adapter.init().await;
+
+        let stored = config_file_accessor.read().await.unwrap().password.unwrap();
+        assert!(password::is_hashed(stored.as_str()));
+        assert!(auth_adapter.validate_password(String::from("secret")).await);
+        assert!(!auth_adapter.validate_password(String::from("wrong")).await);
+    }
+
+    #[tokio::test]
+    async fn totp_code_is_single_use() {
+        let secret = totp::generate_secret();
+        let mut config = Config::new(None);
+        config.totp_secret = Some(secret.clone());
+        let config_file_accessor = Arc::new(MemoryFileAccessor::new(config));
+        let totp_state_file_accessor = Arc::new(MemoryFileAccessor::new(TotpState::new()));
+        let mut auth_adapter = AuthAdapter::new(config_file_accessor.clone(), Arc::new(MemoryFileAccessor::new(ChatList::new())), totp_state_file_accessor.clone());
+        auth_adapter.init().await;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let code = totp::code(secret.as_str(), now).unwrap();
+
+        assert!(auth_adapter.totp_required());
+        assert!(auth_adapter.validate_totp(code.as_str()).await);
+        assert!(!auth_adapter.validate_totp(code.as_str()).await);
+
+        // Still used after a restart
+        let mut restarted = AuthAdapter::new(config_file_accessor, Arc::new(MemoryFileAccessor::new(ChatList::new())), totp_state_file_accessor);
+        restarted.init().await;
+        assert!(!restarted.validate_totp(code.as_str()).await);
+    }
+
+    #[tokio::test]
+    async fn clearing_password_disables_totp() {
+        let mut config = Config::new(Some(String::from("secret")));
+        config.totp_secret = Some(totp::generate_secret());
+        let config_file_accessor = Arc::new(MemoryFileAccessor::new(config));
+        let auth_adapter = AuthAdapter::new(config_file_accessor.clone(), Arc::new(MemoryFileAccessor::new(ChatList::new())), Arc::new(MemoryFileAccessor::new(TotpState::new())));
+
+        auth_adapter.set_password(None).await.unwrap();
+
+        let config = config_file_accessor.read().await.unwrap();
+        assert!(config.password.is_none());
+        assert!(config.totp_secret.is_none());
+    }
+
+    #[tokio::test]
+    async fn revoke_removes_cached_chat() {
+        let chat_list_file_accessor = Arc::new(MemoryFileAccessor::new(ChatList::new()));
+        let mut auth_adapter = AuthAdapter::new(Arc::new(MemoryFileAccessor::new(Config::new(None))), chat_list_file_accessor.clone(), Arc::new(MemoryFileAccessor::new(TotpState::new())));
+        let chat_id = auth_adapter.register(String::from("telegram"), String::from("4")).await.unwrap();
+        assert!(auth_adapter.authenticate(String::from("telegram"), String::from("4")).await.is_some());
+
+        let chat = auth_adapter.revoke(chat_id.as_str()).await.unwrap();
+
+        assert_eq!(chat.identity, "4");
+        assert!(auth_adapter.authenticate(String::from("telegram"), String::from("4")).await.is_none());
+        assert!(chat_list_file_accessor.read().await.unwrap().chats.is_empty());
+        assert!(auth_adapter.revoke(chat_id.as_str()).await.is_err());
+    }
+}
@@ -101,6 +101,13 @@ impl EventSubscribeUseCase for EventConfigAdapter {
         subscribe_file.unsubscribe(event_name.as_str(), chat_id.as_str());
         let _ = self.subscribe_file_accessor.write(&subscribe_file).await?;
 
+        Ok(())
+    }
+    async fn unsubscribe_all(&self, chat_id: String) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut subscribe_file: EventSubscribeList = self.subscribe_file_accessor.read().await?;
+        subscribe_file.remove_chat(chat_id.as_str());
+        self.subscribe_file_accessor.write(&subscribe_file).await?;
+
         Ok(())
     }
 }
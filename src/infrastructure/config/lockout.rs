@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use async_trait::async_trait;
+use derive_new::new;
+use crate::application::config::LockoutUseCase;
+use crate::domain::file_accessor::FileAccessor;
+use crate::domain::lockout::{Attempts, Lockout, LockoutScope, LockoutState, CHAT_LOCKOUT_POLICY, GLOBAL_LOCKOUT_POLICY};
+
+/// Keeps attempts in a file, so restarting `sw run` does not lift a lockout.
+#[derive(new)]
+pub struct LockoutAdapter {
+    lockout_file_accessor: Arc<dyn FileAccessor<LockoutState> + Send + Sync>
+}
+
+impl LockoutAdapter {
+    fn key(client_name: &str, identity: &str) -> String {
+        format!("{client_name}:{identity}")
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl LockoutUseCase for LockoutAdapter {
+    async fn locked_for(&self, client_name: &str, identity: &str) -> Result<Option<u64>, Box<dyn Error + Send + Sync>> {
+        let state = self.lockout_file_accessor.read().await?;
+        let now = Self::now();
+        let chat = state.chats.get(&Self::key(client_name, identity))
+            .and_then(|attempts| attempts.remaining(now));
+        Ok(chat.max(state.global.remaining(now)))
+    }
+
+    async fn record_failure(&self, client_name: &str, identity: &str) -> Result<Option<Lockout>, Box<dyn Error + Send + Sync>> {
+        let mut state = self.lockout_file_accessor.read().await?;
+        let now = Self::now();
+        state.prune(now);
+
+        // Only the failure reaching the threshold starts a lockout, later ones extend it
+        let chat = state.chats.entry(Self::key(client_name, identity)).or_default();
+        let chat_lockout = chat.fail(now, &CHAT_LOCKOUT_POLICY)
+            .filter(|_| chat.failures == CHAT_LOCKOUT_POLICY.threshold)
+            .map(|seconds| Lockout { scope: LockoutScope::Chat, failures: chat.failures, seconds });
+        let global_lockout = state.global.fail(now, &GLOBAL_LOCKOUT_POLICY)
+            .filter(|_| state.global.failures == GLOBAL_LOCKOUT_POLICY.threshold)
+            .map(|seconds| Lockout { scope: LockoutScope::Global, failures: state.global.failures, seconds });
+
+        self.lockout_file_accessor.write(&state).await?;
+        Ok(global_lockout.or(chat_lockout))
+    }
+
+    async fn record_success(&self, client_name: &str, identity: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut state = self.lockout_file_accessor.read().await?;
+        if state.chats.remove(&Self::key(client_name, identity)).is_some() {
+            self.lockout_file_accessor.write(&state).await?;
+        }
+        Ok(())
+    }
+
+    async fn reset(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut state = self.lockout_file_accessor.read().await?;
+        state.chats.clear();
+        state.global = Attempts::default();
+        self.lockout_file_accessor.write(&state).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use std::error::Error;
+use std::sync::Arc;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use derive_new::new;
+use crate::application::config::MaintenanceUseCase;
+use crate::domain::config::{Config, MaintenanceConfig, MAX_MAINTENANCE_DURATION};
+use crate::domain::file_accessor::FileAccessor;
+
+#[derive(new)]
+pub struct MaintenanceAdapter {
+    config_file_accessor: Arc<dyn FileAccessor<Config>>
+}
+
+#[async_trait]
+impl MaintenanceUseCase for MaintenanceAdapter {
+    async fn add_maintenance(&self, maintenance_config: MaintenanceConfig) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if maintenance_config.duration > MAX_MAINTENANCE_DURATION {
+            return Err(anyhow!("Maintenance windows are limited to 7 days").into());
+        }
+        let mut config = self.config_file_accessor.read().await?;
+        if config.maintenance_windows.iter().any(|window| window.name == maintenance_config.name) {
+            return Err(anyhow!("Maintenance window '{}' already exists", maintenance_config.name).into());
+        }
+        config.maintenance_windows.push(maintenance_config);
+        self.config_file_accessor.write(&config).await?;
+        Ok(())
+    }
+
+    async fn list_maintenance(&self) -> Result<Vec<MaintenanceConfig>, Box<dyn Error + Send + Sync>> {
+        let config = self.config_file_accessor.read().await?;
+        Ok(config.maintenance_windows)
+    }
+
+    async fn remove_maintenance(&self, name: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut config = self.config_file_accessor.read().await?;
+        let before = config.maintenance_windows.len();
+        config.maintenance_windows.retain(|window| window.name != name);
+        if config.maintenance_windows.len() == before {
+            return Err(anyhow!("Maintenance window '{name}' not found").into());
+        }
+        self.config_file_accessor.write(&config).await?;
+        Ok(())
+    }
+
+    async fn maintenance_until(&self, server_name: &str) -> Result<Option<DateTime<Local>>, Box<dyn Error + Send + Sync>> {
+        let config = self.config_file_accessor.read().await?;
+        let now = Local::now();
+        Ok(config.maintenance_windows.iter()
+            .filter(|window| window.server_name == server_name)
+            .filter_map(|window| window.active_until(&now))
+            .max())
+    }
+}
@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
+use derive_new::new;
+use uuid::Uuid;
+use crate::application::config::SilenceUseCase;
+use crate::domain::file_accessor::FileAccessor;
+use crate::domain::silence::{Silence, SilenceKind, SilenceList};
+
+#[derive(new)]
+pub struct SilenceAdapter {
+    silence_file_accessor: Arc<dyn FileAccessor<SilenceList>>
+}
+
+#[async_trait]
+impl SilenceUseCase for SilenceAdapter {
+    async fn silence(&self, target: String, kind: SilenceKind, duration: Duration, reason: Option<String>, client_name: String, identity: String) -> Result<Silence, Box<dyn Error + Send + Sync>> {
+        let until = TimeDelta::from_std(duration).ok()
+            .and_then(|duration| Utc::now().checked_add_signed(duration))
+            .ok_or_else(|| anyhow!("Duration is too long"))?;
+        let mut silence_list = self.silence_file_accessor.read().await?;
+        let silence = Silence {
+            id: Uuid::new_v4().simple().to_string()[..8].to_string(),
+            target,
+            kind,
+            until,
+            reason,
+            client_name,
+            identity
+        };
+        silence_list.silences.push(silence.clone());
+        self.silence_file_accessor.write(&silence_list).await?;
+        Ok(silence)
+    }
+
+    async fn list_silences(&self) -> Result<Vec<Silence>, Box<dyn Error + Send + Sync>> {
+        let now = Utc::now();
+        Ok(self.silence_file_accessor.read().await?
+            .silences
+            .into_iter()
+            .filter(|silence| !silence.is_expired(now))
+            .collect())
+    }
+
+    async fn unsilence(&self, id: &str) -> Result<Silence, Box<dyn Error + Send + Sync>> {
+        let mut silence_list = self.silence_file_accessor.read().await?;
+        let silence = silence_list.remove(id)
+            .ok_or_else(|| anyhow!("Silence '{id}' not found"))?;
+        self.silence_file_accessor.write(&silence_list).await?;
+        Ok(silence)
+    }
+
+    async fn find_silence(&self, event_name: &str, server_name: &str) -> Result<Option<Silence>, Box<dyn Error + Send + Sync>> {
+        Ok(self.silence_file_accessor.read().await?
+            .find(event_name, server_name, Utc::now())
+            .cloned())
+    }
+
+    async fn take_expired(&self) -> Result<Vec<Silence>, Box<dyn Error + Send + Sync>> {
+        let mut silence_list = self.silence_file_accessor.read().await?;
+        let expired = silence_list.take_expired(Utc::now());
+        if !expired.is_empty() {
+            self.silence_file_accessor.write(&silence_list).await?;
+        }
+        Ok(expired)
+    }
+}
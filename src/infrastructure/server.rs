@@ -2,6 +2,7 @@ mod child_process_stream;
 mod http_server_client;
 mod std_log_reader;
 mod docker;
+mod restarter;
 pub mod util;
 
 use std::collections::HashMap;
@@ -16,6 +17,7 @@ use crate::domain::server::{health::Health, Server};
 use crate::domain::server::health::HealthCheckMethod;
 use crate::infrastructure::server::docker::DockerHealthChecker;
 use crate::infrastructure::server::http_server_client::HttpServerClient;
+use crate::infrastructure::server::restarter::ServerRestarter;
 use crate::infrastructure::server::std_log_reader::StdLogReader;
 
 #[derive(new)]
@@ -58,6 +60,7 @@ pub struct GeneralServerManager {
     http_server_client: HttpServerClient,
     std_log_reader: StdLogReader,
     docker_health_checker: DockerHealthChecker,
+    server_restarter: ServerRestarter,
 }
 
 impl GeneralServerManager {
@@ -67,6 +70,7 @@ impl GeneralServerManager {
             http_server_client: HttpServerClient::new(),
             std_log_reader: StdLogReader::new(),
             docker_health_checker: DockerHealthChecker::new(),
+            server_restarter: ServerRestarter::new(),
         }
     }
 }
@@ -82,6 +86,15 @@ impl ServerManager for GeneralServerManager {
         self.http_server_client.kill(server).await
     }
 
+    async fn restart(&self, name: &str) -> bool {
+        let server = match self.server_repository.find(name) {
+            Some(s) => s,
+            None => return false
+        };
+
+        self.server_restarter.restart(server).await
+    }
+
     async fn healthcheck(&self, name: &str) -> Health {
         let server = match self.server_repository.find(name) {
             Some(s) => s,
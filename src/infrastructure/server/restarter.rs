@@ -0,0 +1,85 @@
+use log::{error, info};
+use crate::domain::config::RestartMethod;
+use crate::domain::server::Server;
+use crate::infrastructure::server::util::SystemCommandExecutor;
+
+pub struct ServerRestarter {
+    system_command_executor: SystemCommandExecutor
+}
+
+impl ServerRestarter {
+    pub fn new() -> Self {
+        Self {
+            system_command_executor: SystemCommandExecutor::new()
+        }
+    }
+
+    pub async fn restart(&self, server: &Server) -> bool {
+        let restart_method = match &server.restart_method {
+            Some(value) => value,
+            None => {
+                error!("[ServerRestarter] Err: Restart method undefined for '{}'", server.name);
+                return false
+            }
+        };
+
+        let success = match restart_method {
+            RestartMethod::Docker => {
+                let container_name = match &server.docker_container_name {
+                    Some(value) => value,
+                    None => {
+                        error!("[ServerRestarter] Err: Container name undefined for '{}'", server.name);
+                        return false
+                    }
+                };
+                self.system_command_executor
+                    .execute("docker", &["restart", container_name.as_str()]).await
+            },
+            RestartMethod::Systemd { unit } => {
+                self.system_command_executor
+                    .execute("systemctl", &["restart", unit.as_str()]).await
+            },
+            RestartMethod::Command { command } => {
+                let args: Vec<&str> = command.split_whitespace().collect();
+                match args.split_first() {
+                    Some((cmd, args)) => self.system_command_executor.execute(cmd, args).await,
+                    None => false
+                }
+            }
+        };
+
+        if success {
+            info!("[ServerRestarter] Info: '{}' restarted", server.name);
+        } else {
+            error!("[ServerRestarter] Err: Fail to restart '{}'", server.name);
+        }
+        success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::config::ServerConfig;
+
+    fn server(restart: Option<RestartMethod>) -> Server {
+        Server::from(ServerConfig::new(String::from("main"), None, None, None, None, None, restart))
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn restart_runs_custom_command() {
+        let restarter = ServerRestarter::new();
+
+        assert!(restarter.restart(&server(Some(RestartMethod::Command { command: String::from("true") }))).await);
+        assert!(!restarter.restart(&server(Some(RestartMethod::Command { command: String::from("false") }))).await);
+    }
+
+    #[tokio::test]
+    async fn restart_fails_without_method_or_container() {
+        let restarter = ServerRestarter::new();
+
+        assert!(!restarter.restart(&server(None)).await);
+        assert!(!restarter.restart(&server(Some(RestartMethod::Docker))).await);
+    }
+}